@@ -1,31 +1,61 @@
+use arc_swap::ArcSwap;
+use crossbeam::{channel, select};
 use eframe::{
     App,
-    egui::{CentralPanel, MenuBar, TopBottomPanel},
+    egui::{CentralPanel, Id, MenuBar, Modal, TopBottomPanel, ViewportCommand},
 };
-use egui_tiles::{Behavior, Tiles, Tree};
-use futures::channel::mpsc;
+use egui_tiles::{Behavior, SimplificationOptions, Tile, TileId, Tiles, Tree};
 use lunaris_api::plugin::{GuiRegistration, PluginContext};
 use lunaris_ecs::prelude::*;
-use slab::Slab;
 use std::{
-    collections::{HashMap, HashSet},
-    sync::{Arc, RwLock},
+    collections::{HashMap, HashSet, VecDeque, hash_map::Entry},
+    path::PathBuf,
+    sync::Arc,
     thread::{self, JoinHandle},
+    time::{Duration, Instant},
 };
+use tracing::{debug, error, info, warn};
 
 use crate::{
-    bridge::SharedState,
-    orchestrator::Orchestrator,
-    plugin::{GuiPluginNode, PluginNode},
+    bridge::{PluginId, SharedState, SharedStateHandle, WorldStats, plugin_id},
+    config::LunarisConfig,
+    dispatcher::{PendingFrames, dispatch_frames, pacing::FramePacer},
+    kernel,
+    oops::Oops,
+    orchestrator::{Orchestrator, history::DEFAULT_SAMPLE_INTERVAL},
+    plugin::{GuiPluginNode, PluginNode, ShutdownVote},
+    registry::{
+        PluginRegistry, PluginReports, collect_reports, init_plugins, tick_plugins, with_plugins,
+    },
+    services::Services,
+    signals, teardown,
 };
 
-type PluginId = usize;
+/// Interval between world ticks (~60 FPS).
+const TICK_INTERVAL: Duration = Duration::from_millis(16);
+/// Tick durations kept in `WorldStats::recent_ticks` (~2s at 60 ticks per second).
+const TICK_HISTORY_LEN: usize = 120;
+/// eframe storage key of the persisted tile layout.
+const LAYOUT_KEY: &str = "layout";
+/// How long closing waits for the world thread to poll its plugins' `can_shutdown`.
+const SHUTDOWN_VOTE_TIMEOUT: Duration = Duration::from_millis(500);
+/// How long closing the app waits for the world thread before detaching it.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 
 // --- Data structures for cross-thread communication ---
 
 /// Commands sent from the UI thread to the World thread.
 enum WorldCommand {
     Quit,
+    /// Switch projects: frame work for the current one is cancelled.
+    LoadProject(PathBuf),
+    /// Stop running the schedule until `Resume`, without quitting.
+    Pause,
+    Resume,
+    /// Poll the registry's `can_shutdown` and send the votes back.
+    ShutdownVotes(channel::Sender<Vec<(&'static str, ShutdownVote)>>),
+    /// Write a `KernelSnapshot` next to the config.
+    Snapshot,
     // Add other commands here, e.g., for user interactions
 }
 
@@ -33,94 +63,428 @@ pub struct LunarisApp {
     /// Handle to the dedicated world thread.
     world_thread: Option<JoinHandle<()>>,
     /// Sender to send commands to the world thread.
-    command_sender: mpsc::Sender<WorldCommand>,
+    command_sender: channel::Sender<WorldCommand>,
+    /// Disconnects when the world thread exits (normally or by panic); never carries messages.
+    world_exited: channel::Receiver<()>,
+    /// Latest snapshot published by the world thread.
+    ui_state: SharedStateHandle,
+    /// Unregisters the Ctrl-C hook when the app goes away.
+    _shutdown_hook: signals::ShutdownHookHandle,
+    /// Shares the world orchestrator's worker pool; handed to plugins on the UI thread.
+    orchestrator: Orchestrator,
+    shutdown_timeout: Duration,
+    /// Set when closing starts; the window stays open until the world thread has exited.
+    closing_since: Option<Instant>,
+    /// Plugins asking the user to confirm quitting, with their reasons. Set while the
+    /// confirmation dialog is open.
+    unconfirmed: Option<Vec<(&'static str, String)>>,
 
     // The following fields are purely for the UI and are managed only on the UI thread.
-    plugins: Slab<Box<dyn PluginNode>>,
+    plugins: HashMap<PluginId, Box<dyn PluginNode>>,
     tree: Tree<PluginId>,
     gui_index_by_name: HashMap<&'static str, PluginId>,
+    /// Every discovered GUI plugin, enabled or not.
+    gui_registrations: Vec<&'static GuiRegistration>,
+    config: LunarisConfig,
     last_tab_container_id: Option<egui_tiles::TileId>,
 }
 
 impl Default for LunarisApp {
     fn default() -> Self {
-        let (command_sender, mut command_receiver) = mpsc::channel(8);
-        let ui_state = Arc::new(RwLock::new(SharedState::default()));
+        Self::with_config(LunarisConfig::load())
+    }
+}
+
+impl LunarisApp {
+    pub fn with_config(config: LunarisConfig) -> Self {
+        let (command_sender, command_receiver) = channel::bounded(8);
+        let ui_state: SharedStateHandle = Arc::new(ArcSwap::from_pointee(SharedState::default()));
         let ui_state_clone = ui_state.clone();
+        let (exit_guard, world_exited) = channel::bounded::<()>(0);
+        let orchestrator = Orchestrator::default();
+        orchestrator.start_profile_sampling(DEFAULT_SAMPLE_INTERVAL);
+        let ui_orchestrator = orchestrator.share();
 
         // --- Spawn the dedicated World thread ---
         let world_thread = thread::spawn(move || {
+            let _exit_guard = exit_guard;
             let mut world = World::new();
             let mut schedule = Schedule::default();
 
             // --- Initialize World Resources ---
-            world.insert_resource(Orchestrator::default());
+            world.insert_resource(orchestrator);
+            world.insert_resource(PluginRegistry::default());
+            world.insert_resource(PluginReports::default());
+            world.insert_resource(PendingFrames::default());
+            world.insert_resource(FramePacer::default());
+            world.insert_resource(Services::default());
+            init_plugins(&mut world);
+
+            // Plugins update the world first, then the frames they queued are dispatched,
+            // then reports reflect the finished tick.
+            schedule.add_systems((tick_plugins, dispatch_frames, collect_reports).chain());
 
             // --- Main World Loop ---
+            // Commands are handled as soon as they arrive; ticks fire on their own schedule.
+            let ticker = channel::tick(TICK_INTERVAL);
+            let mut stats = WorldStats::default();
+            let mut recent_ticks = VecDeque::with_capacity(TICK_HISTORY_LEN);
+            // Publish a fresh shared UI state snapshot for the next frame
+            let publish = |stats: &WorldStats| {
+                let state = SharedState::with_world_stats(stats.clone());
+                // e.g., state.insert(id, Box::new(world.resource::<MyResource>().snapshot()));
+                ui_state_clone.store(Arc::new(state));
+            };
             loop {
-                // Check for commands from the UI thread
-                match command_receiver.try_next() {
-                    Ok(Some(WorldCommand::Quit)) => {
-                        println!("World thread received quit command.");
-                        break;
-                    }
-                    Ok(None) => {
+                select! {
+                    recv(command_receiver) -> command => match command {
+                        Ok(WorldCommand::Quit) => {
+                            kernel::begin_shutdown();
+                            println!("World thread received quit command.");
+                            break;
+                        }
+                        Ok(WorldCommand::LoadProject(path)) => {
+                            info!("Loading project {}", path.display());
+                            world.resource_mut::<PendingFrames>().clear();
+                            let epoch = world.resource::<Orchestrator>().advance_render_epoch();
+                            debug!("Render epoch advanced to {epoch}");
+                        }
+                        Ok(WorldCommand::Pause) => {
+                            info!("World paused.");
+                            stats.paused = true;
+                            publish(&stats);
+                        }
+                        Ok(WorldCommand::Resume) => {
+                            info!("World resumed.");
+                            stats.paused = false;
+                            publish(&stats);
+                        }
+                        Ok(WorldCommand::ShutdownVotes(reply)) => {
+                            let mut votes = Vec::new();
+                            with_plugins(&mut world, |world, orch, registry| {
+                                votes = registry.shutdown_votes(world, orch);
+                            });
+                            reply.send(votes).ok();
+                        }
+                        Ok(WorldCommand::Snapshot) => {
+                            let shared = ui_state_clone.load();
+                            match kernel::snapshot(&world, Some(&**shared)).write() {
+                                Ok(path) => {
+                                    info!("Wrote diagnostic snapshot {}", path.display())
+                                }
+                                Err(e) => error!("Could not write diagnostic snapshot: {e}"),
+                            }
+                        }
                         // Channel closed, should also quit
-                        break;
-                    }
-                    _ => {}
-                }
-
-                // Run all systems in the schedule!
-                schedule.run(&mut world);
+                        Err(_) => break,
+                    },
+                    recv(ticker) -> _ => {
+                        // Set by SIGINT; see the `on_shutdown` hook below.
+                        if kernel::is_shutting_down() {
+                            break;
+                        }
+                        if stats.paused {
+                            continue;
+                        }
+                        let started = Instant::now();
+                        // Run all systems in the schedule!
+                        schedule.run(&mut world);
 
-                // Update the shared UI state for the next frame
-                if let Ok(state) = ui_state_clone.write() {
-                    // e.g., state.some_value = world.get_resource::<MyResource>().unwrap().some_value;
+                        let elapsed = started.elapsed();
+                        stats.ticks += 1;
+                        if elapsed > TICK_INTERVAL {
+                            stats.overruns += 1;
+                            warn!("World tick overran: {elapsed:?} > {TICK_INTERVAL:?}");
+                        }
+                        if recent_ticks.len() == TICK_HISTORY_LEN {
+                            recent_ticks.pop_front();
+                        }
+                        recent_ticks.push_back(elapsed);
+                        stats.recent_ticks = recent_ticks.iter().copied().collect();
+                        publish(&stats);
+                    },
                 }
-
-                // Sleep to prevent busy-looping and yield CPU time
-                thread::sleep(std::time::Duration::from_millis(16)); // ~60 FPS
             }
+            teardown::shutdown(&mut world, schedule);
         });
 
         // --- Initialize UI-specific state ---
-        let mut tiles: Tiles<PluginId> = Tiles::default();
-        let mut plugins: Slab<Box<dyn PluginNode>> = Slab::new();
+        let mut plugins: HashMap<PluginId, Box<dyn PluginNode>> = HashMap::new();
         let mut gui_index_by_name: HashMap<&'static str, PluginId> = HashMap::new();
-        let mut gui_names: HashSet<&'static str> = HashSet::new();
+        let mut gui_registrations: Vec<&'static GuiRegistration> = Vec::new();
         let mut gui_ids: Vec<PluginId> = Vec::new();
 
+        // Every plugin is discovered, but only enabled ones are instantiated.
         for reg in inventory::iter::<GuiRegistration> {
-            let id = plugins.insert(Box::new(GuiPluginNode::new((reg.build)())));
+            gui_registrations.push(reg);
+            if !config.is_enabled(reg.name) {
+                continue;
+            }
+            let id = plugin_id(reg.name);
+            match plugins.entry(id) {
+                Entry::Occupied(_) => {
+                    error!("Plugin id collision for \"{}\"; skipping it.", reg.name);
+                    continue;
+                }
+                Entry::Vacant(slot) => {
+                    slot.insert(Box::new(GuiPluginNode::new((reg.build)())));
+                }
+            }
             gui_index_by_name.insert(reg.name, id);
-            gui_names.insert(reg.name);
             gui_ids.push(id);
         }
 
-        let tileids: Vec<_> = gui_ids
-            .iter()
-            .copied()
-            .map(|id| tiles.insert_pane(id))
-            .collect();
-        let root = tiles.insert_tab_tile(tileids);
+        if gui_registrations.is_empty() {
+            warn!("No GUI plugins were found; was the plugin set linked in?");
+        }
+
+        // Ctrl-C stops the world thread the same way closing the window does: the kernel is
+        // already shutting down when the hook runs, which the world thread sees on its next
+        // tick. The hook holds no sender, so it never keeps the command channel open.
+        let exited = world_exited.clone();
+        let shutdown_hook = signals::on_shutdown(Arc::new(move || {
+            // Disconnects once the world thread has exited.
+            let _ = exited.recv();
+        }));
 
         Self {
             world_thread: Some(world_thread),
             command_sender,
+            world_exited,
+            ui_state,
+            _shutdown_hook: shutdown_hook,
+            orchestrator: ui_orchestrator,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            closing_since: None,
+            unconfirmed: None,
             plugins,
-            tree: Tree::new("main_tree", root, tiles),
+            tree: default_tree(&gui_ids),
             gui_index_by_name,
+            gui_registrations,
+            config,
             last_tab_container_id: None,
         }
     }
+
+    /// Override how long closing waits for the world thread (default `DEFAULT_SHUTDOWN_TIMEOUT`).
+    pub fn with_shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = timeout;
+        self
+    }
+
+    /// Enable or disable a GUI plugin at runtime and persist the choice.
+    fn set_plugin_enabled(&mut self, name: &'static str, enabled: bool) {
+        self.config.set_enabled(name, enabled);
+        if let Err(e) = self.config.save() {
+            warn!("Could not save config: {e}");
+        }
+        if enabled {
+            let Some(reg) = self.gui_registrations.iter().find(|r| r.name == name) else {
+                return;
+            };
+            if self.gui_index_by_name.contains_key(name) {
+                return;
+            }
+            let id = plugin_id(name);
+            if self.plugins.contains_key(&id) {
+                error!("Plugin id collision for \"{name}\"; not enabling it.");
+                return;
+            }
+            self.plugins
+                .insert(id, Box::new(GuiPluginNode::new((reg.build)())));
+            self.gui_index_by_name.insert(name, id);
+            add_pane(&mut self.tree, id);
+        } else if let Some(id) = self.gui_index_by_name.remove(name) {
+            if let Some(tile) = self.tree.tiles.find_pane(&id) {
+                self.tree.remove_recursively(tile);
+            }
+            self.plugins.remove(&id);
+        }
+    }
+
+    /// Load another project, cancelling queued and pending frame work for the current one.
+    pub fn load_project(&self, path: PathBuf) {
+        if self
+            .command_sender
+            .try_send(WorldCommand::LoadProject(path))
+            .is_err()
+        {
+            warn!("World thread is busy or gone; project load dropped.");
+        }
+    }
+
+    /// Suspend or resume world ticking. `SharedState::world_stats().paused` follows once the
+    /// world thread has handled the command.
+    pub fn set_paused(&self, paused: bool) {
+        let command = if paused {
+            WorldCommand::Pause
+        } else {
+            WorldCommand::Resume
+        };
+        if self.command_sender.try_send(command).is_err() {
+            warn!("World thread is busy or gone; pause toggle dropped.");
+        }
+    }
+
+    /// Ask the world thread to write a diagnostic `KernelSnapshot`; see `KernelSnapshot::write`.
+    pub fn request_snapshot(&self) {
+        if self
+            .command_sender
+            .try_send(WorldCommand::Snapshot)
+            .is_err()
+        {
+            warn!("World thread is busy or gone; snapshot request dropped.");
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.ui_state.load().world_stats().paused
+    }
+
+    /// Replace the default layout with the one saved by a previous run, if any.
+    pub fn restore_layout(self, storage: Option<&dyn eframe::Storage>) -> Self {
+        match storage.and_then(|s| eframe::get_value::<Tree<PluginId>>(s, LAYOUT_KEY)) {
+            Some(tree) => self.with_layout(tree),
+            None => self,
+        }
+    }
+
+    /// Use `tree` as the layout after repairing it against the plugins enabled now; enabled
+    /// plugins it lacks are appended. An unusable tree leaves the current layout in place.
+    pub fn with_layout(mut self, mut tree: Tree<PluginId>) -> Self {
+        let known: HashSet<PluginId> = self.plugins.keys().copied().collect();
+        if !validate_and_repair(&mut tree, &known) {
+            warn!("Saved layout is unusable; using the default layout.");
+            return self;
+        }
+        add_missing_panes(&mut tree, self.gui_index_by_name.values().copied());
+        self.tree = tree;
+        self
+    }
+
+    /// Poll `can_shutdown` of every GUI plugin and, through the world thread, every registry
+    /// plugin, and start closing if they all allow it. A denial cancels the close;
+    /// confirmation requests are collected for the user.
+    fn request_close(&mut self) {
+        let dummy_world = &mut World::new();
+        let mut votes: Vec<_> = self
+            .plugins
+            .values()
+            .map(|p| {
+                let ctx = PluginContext {
+                    world: &mut *dummy_world,
+                    orch: &self.orchestrator,
+                };
+                (p.name(), p.can_shutdown(ctx))
+            })
+            .collect();
+        votes.extend(self.registry_shutdown_votes());
+        let mut reasons = Vec::new();
+        for (name, vote) in votes {
+            match vote {
+                ShutdownVote::Allow => {}
+                ShutdownVote::NeedsConfirmation(reason) => reasons.push((name, reason)),
+                ShutdownVote::Deny => {
+                    warn!("Plugin {name} refused to shut down.");
+                    return;
+                }
+            }
+        }
+        if reasons.is_empty() {
+            self.begin_close();
+        } else {
+            self.unconfirmed = Some(reasons);
+        }
+    }
+
+    /// Registry plugins' votes, collected on the world thread. A world thread that is busy
+    /// for longer than `SHUTDOWN_VOTE_TIMEOUT`, or gone, has no say.
+    fn registry_shutdown_votes(&self) -> Vec<(&'static str, ShutdownVote)> {
+        let (reply, votes) = channel::bounded(1);
+        if self
+            .command_sender
+            .try_send(WorldCommand::ShutdownVotes(reply))
+            .is_err()
+        {
+            warn!("World thread is busy or gone; closing without asking its plugins.");
+            return Vec::new();
+        }
+        votes
+            .recv_timeout(SHUTDOWN_VOTE_TIMEOUT)
+            .unwrap_or_else(|_| {
+                warn!("World thread did not answer in time; closing without asking its plugins.");
+                Vec::new()
+            })
+    }
+
+    /// Ask the world thread to quit without waiting for it. `poll_world_exit` finishes the job.
+    fn begin_close(&mut self) {
+        if self.closing_since.is_none() {
+            self.command_sender.try_send(WorldCommand::Quit).ok();
+            self.closing_since = Some(Instant::now());
+        }
+    }
+
+    /// Non-blocking check used while closing. Returns `true` once the world thread has exited,
+    /// or has been detached for exceeding `shutdown_timeout`.
+    fn poll_world_exit(&mut self) -> bool {
+        let Some(since) = self.closing_since else {
+            return false;
+        };
+        match self.world_exited.try_recv() {
+            Err(channel::TryRecvError::Disconnected) => {
+                self.join_world();
+                true
+            }
+            _ if since.elapsed() >= self.shutdown_timeout => {
+                self.detach_world();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Ask the world thread to quit and wait for it, at most `shutdown_timeout`. A wedged world
+    /// thread is detached rather than allowed to block closing the app.
+    fn stop_world(&mut self) {
+        if self.world_thread.is_none() {
+            return;
+        }
+        self.begin_close();
+        match self.world_exited.recv_timeout(self.shutdown_timeout) {
+            Err(channel::RecvTimeoutError::Timeout) => self.detach_world(),
+            _ => self.join_world(),
+        }
+    }
+
+    /// Join an exited world thread. A panic on the world thread is reported to the user
+    /// instead of being re-raised on the UI thread.
+    fn join_world(&mut self) {
+        if let Some(thread) = self.world_thread.take()
+            && let Err(payload) = thread.join()
+        {
+            let oops = Oops::from_panic(payload.as_ref());
+            error!("World thread panicked.");
+            oops.popup();
+        }
+    }
+
+    fn detach_world(&mut self) {
+        if self.world_thread.take().is_some() {
+            error!(
+                "World thread did not exit within {:?}; detaching it.",
+                self.shutdown_timeout
+            );
+        }
+    }
 }
 
 // The AppBehavior now needs to be adapted to the new architecture.
-// For now, we'll pass dummy data to the plugins' UI methods.
+// For now, we'll pass a dummy world to the plugins' UI methods.
 struct AppBehavior<'a> {
-    plugins: &'a mut Slab<Box<dyn PluginNode>>,
-    // We no longer have direct access to the World or Orchestrator here.
+    plugins: &'a mut HashMap<PluginId, Box<dyn PluginNode>>,
+    // We no longer have direct access to the World here.
+    orch: &'a Orchestrator,
 }
 
 impl<'a> Behavior<PluginId> for AppBehavior<'a> {
@@ -133,21 +497,39 @@ impl<'a> Behavior<PluginId> for AppBehavior<'a> {
         // This is a temporary solution. A proper implementation would require
         // the UI plugins to get their state from the `SharedUiState`.
         let dummy_world = &mut World::new();
-        let dummy_orch = &Orchestrator::default() as &dyn lunaris_api::request::DynOrchestrator;
-        let ctx = PluginContext {
-            world: dummy_world,
-            orch: dummy_orch,
-        };
 
-        if let Some(p) = self.plugins.get(*pane) {
-            p.ui(ui, ctx);
-        }
+        let Some(p) = self.plugins.get(pane) else {
+            return egui_tiles::UiResponse::None;
+        };
+        // Registered before the plugin's widgets so they stay on top; only right-clicks on
+        // the pane background reach it.
+        let background = ui.interact(
+            ui.max_rect(),
+            ui.id().with("pane_background"),
+            eframe::egui::Sense::click(),
+        );
+        p.ui(
+            ui,
+            PluginContext {
+                world: &mut *dummy_world,
+                orch: self.orch,
+            },
+        );
+        background.context_menu(|ui| {
+            p.context_menu(
+                ui,
+                PluginContext {
+                    world: &mut *dummy_world,
+                    orch: self.orch,
+                },
+            );
+        });
         egui_tiles::UiResponse::None
     }
 
     fn tab_title_for_pane(&mut self, pane: &PluginId) -> eframe::egui::WidgetText {
         self.plugins
-            .get(*pane)
+            .get(pane)
             .map_or("<missing>".into(), |p| p.name().into())
     }
 
@@ -166,44 +548,303 @@ impl<'a> Behavior<PluginId> for AppBehavior<'a> {
 }
 
 impl App for LunarisApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        // A workspace's layout belongs to the workspace, not to the user's saved layout.
+        if !self.config.read_only {
+            eframe::set_value(storage, LAYOUT_KEY, &self.tree);
+        }
+    }
+
     fn update(&mut self, ctx: &eframe::egui::Context, _frame: &mut eframe::Frame) {
         // The UI thread is now much simpler. It just draws the UI.
         // The complex logic and system updates are all happening in the background.
 
-        if ctx.input(|i| i.viewport().close_requested()) {
-            // When the user tries to close the window, send the Quit command.
-            if let Some(thread) = self.world_thread.take() {
-                self.command_sender.try_send(WorldCommand::Quit).ok();
-                thread.join().expect("World thread panicked!");
+        if ctx.input(|i| i.viewport().close_requested()) && self.world_thread.is_some() {
+            // When the user tries to close the window, send the Quit command and keep the
+            // window (and event loop) alive until the world thread has wound down.
+            ctx.send_viewport_cmd(ViewportCommand::CancelClose);
+            if self.closing_since.is_none() && self.unconfirmed.is_none() {
+                self.request_close();
+            }
+        }
+        if let Some(reasons) = &self.unconfirmed {
+            let mut answer = None;
+            Modal::new(Id::new("confirm_shutdown")).show(ctx, |ui| {
+                ui.heading("Quit Lunaris?");
+                for (plugin, reason) in reasons {
+                    ui.label(format!("{plugin}: {reason}"));
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("Quit anyway").clicked() {
+                        answer = Some(true);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        answer = Some(false);
+                    }
+                });
+            });
+            if let Some(quit) = answer {
+                self.unconfirmed = None;
+                if quit {
+                    self.begin_close();
+                }
+            }
+        }
+        if self.closing_since.is_some() {
+            if self.poll_world_exit() {
+                // Actually close the window now that the thread is gone.
+                ctx.send_viewport_cmd(ViewportCommand::Close);
+            } else {
+                Modal::new(Id::new("shutdown_overlay")).show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Shutting down…");
+                    });
+                });
+                ctx.request_repaint_after(TICK_INTERVAL);
             }
-            // Actually close the window now that the thread is joined.
-            ctx.send_viewport_cmd(eframe::egui::ViewportCommand::Close);
         }
 
-        let mut behavior = AppBehavior {
-            plugins: &mut self.plugins,
-        };
+        if self.config.window.persist_window {
+            let (inner, outer) = ctx.input(|i| (i.viewport().inner_rect, i.viewport().outer_rect));
+            if let Some(inner) = inner {
+                self.config.window.size = [inner.width(), inner.height()];
+            }
+            if let Some(outer) = outer {
+                self.config.window.position = Some([outer.min.x, outer.min.y]);
+            }
+        }
 
+        let paused = self.is_paused();
+        let mut toggle_pause = false;
+        let mut snapshot = false;
+        let mut toggled: Vec<(&'static str, bool)> = Vec::new();
+        // Shared chrome regions, filled by plugins in registration order.
+        let ordered: Vec<PluginId> = self
+            .gui_registrations
+            .iter()
+            .filter_map(|reg| self.gui_index_by_name.get(reg.name).copied())
+            .collect();
         TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             MenuBar::new().ui(ui, |ui| {
                 ui.menu_button("File", |ui| {
+                    if ui.button("Write diagnostic snapshot").clicked() {
+                        snapshot = true;
+                    }
                     if ui.button("Quit").clicked() {
                         // This will trigger the close sequence on the next frame.
-                        ctx.send_viewport_cmd(eframe::egui::ViewportCommand::Close);
+                        ctx.send_viewport_cmd(ViewportCommand::Close);
+                    }
+                });
+                ui.menu_button("World", |ui| {
+                    let label = if paused { "Resume" } else { "Pause" };
+                    if ui.button(label).clicked() {
+                        toggle_pause = true;
+                    }
+                });
+                ui.menu_button("Plugins", |ui| {
+                    for reg in &self.gui_registrations {
+                        let mut enabled = self.config.is_enabled(reg.name);
+                        // Only instantiated plugins can describe themselves.
+                        let meta = self
+                            .gui_index_by_name
+                            .get(reg.name)
+                            .and_then(|id| self.plugins.get(id))
+                            .map(|p| p.metadata());
+                        let label = match meta.as_ref().and_then(|m| m.version) {
+                            Some(version) => format!("{} {version}", reg.name),
+                            None => reg.name.to_string(),
+                        };
+                        let mut response = ui.checkbox(&mut enabled, label);
+                        if let Some(meta) = &meta
+                            && (meta.author.is_some() || meta.description.is_some())
+                        {
+                            response = response.on_hover_ui(|ui| {
+                                if let Some(author) = meta.author {
+                                    ui.label(format!("by {author}"));
+                                }
+                                if let Some(description) = meta.description {
+                                    ui.label(description);
+                                }
+                            });
+                        }
+                        if response.changed() {
+                            toggled.push((reg.name, enabled));
+                        }
                     }
                 });
             });
+            ui.horizontal(|ui| {
+                for id in &ordered {
+                    if let Some(p) = self.plugins.get(id) {
+                        p.top_bar_ui(ui);
+                    }
+                }
+            });
         });
-        CentralPanel::default().show(ctx, |ui| self.tree.ui(&mut behavior, ui));
+        if toggle_pause {
+            self.set_paused(!paused);
+        }
+        if snapshot {
+            self.request_snapshot();
+        }
+        for (name, enabled) in toggled {
+            self.set_plugin_enabled(name, enabled);
+        }
+
+        TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if paused {
+                    ui.label("⏸ Paused");
+                    ui.separator();
+                }
+                for id in &ordered {
+                    if let Some(p) = self.plugins.get(id) {
+                        p.status_bar_ui(ui);
+                    }
+                }
+            });
+        });
+
+        let mut behavior = AppBehavior {
+            plugins: &mut self.plugins,
+            orch: &self.orchestrator,
+        };
+        CentralPanel::default().show(ctx, |ui| {
+            if self.gui_registrations.is_empty() {
+                placeholder_ui(
+                    ui,
+                    "No plugins found",
+                    "Lunaris was built without any GUI plugins. Plugins are linked in \
+                     through the `linker` crate; add a plugin crate there and rebuild.",
+                );
+            } else if self.gui_index_by_name.is_empty() {
+                placeholder_ui(
+                    ui,
+                    "All plugins are disabled",
+                    "Enable plugins from the Plugins menu.",
+                );
+            } else {
+                self.tree.ui(&mut behavior, ui);
+            }
+        });
+    }
+}
+
+/// A single tab container holding one pane per id, in order.
+fn default_tree(ids: &[PluginId]) -> Tree<PluginId> {
+    let mut tiles: Tiles<PluginId> = Tiles::default();
+    let panes = ids.iter().map(|id| tiles.insert_pane(*id)).collect();
+    let root = tiles.insert_tab_tile(panes);
+    Tree::new("main_tree", root, tiles)
+}
+
+/// Add a pane for `id` to the root container, creating or wrapping the root as needed.
+fn add_pane(tree: &mut Tree<PluginId>, id: PluginId) {
+    let pane = tree.tiles.insert_pane(id);
+    match tree.root() {
+        Some(root) => {
+            if let Some(Tile::Container(container)) = tree.tiles.get_mut(root) {
+                container.add_child(pane);
+            } else {
+                tree.root = Some(tree.tiles.insert_tab_tile(vec![root, pane]));
+            }
+        }
+        None => tree.root = Some(tree.tiles.insert_tab_tile(vec![pane])),
+    }
+}
+
+/// Add a pane for each of `ids` that `tree` does not show yet.
+fn add_missing_panes(tree: &mut Tree<PluginId>, ids: impl IntoIterator<Item = PluginId>) {
+    for id in ids {
+        if tree.tiles.find_pane(&id).is_none() {
+            add_pane(tree, id);
+        }
     }
 }
 
+/// Make a deserialized layout safe to use with the plugins in `known`: panes for unknown or
+/// duplicate ids are removed and empty containers pruned. Returns `false` if no valid root
+/// remains, in which case the caller should fall back to `default_tree`.
+pub fn validate_and_repair(tree: &mut Tree<PluginId>, known: &HashSet<PluginId>) -> bool {
+    let mut seen = HashSet::new();
+    let stale: Vec<TileId> = tree
+        .tiles
+        .iter()
+        .filter_map(|(tile_id, tile)| match tile {
+            Tile::Pane(id) if !known.contains(id) || !seen.insert(*id) => Some(*tile_id),
+            _ => None,
+        })
+        .collect();
+    for tile_id in stale {
+        tree.remove_recursively(tile_id);
+    }
+    tree.simplify(&SimplificationOptions {
+        prune_empty_tabs: true,
+        prune_empty_containers: true,
+        ..Default::default()
+    });
+    tree.root()
+        .is_some_and(|root| tree.tiles.get(root).is_some())
+}
+
+/// Centered explanation shown instead of the tile tree when there is nothing to show.
+fn placeholder_ui(ui: &mut eframe::egui::Ui, title: &str, detail: &str) {
+    ui.centered_and_justified(|ui| {
+        ui.vertical_centered(|ui| {
+            ui.heading(title);
+            ui.label(detail);
+        });
+    });
+}
+
 impl Drop for LunarisApp {
     fn drop(&mut self) {
         // Ensure the world thread is shut down cleanly when the app is dropped.
-        if let Some(thread) = self.world_thread.take() {
-            self.command_sender.try_send(WorldCommand::Quit).ok();
-            thread.join().expect("World thread panicked!");
+        self.stop_world();
+        if self.config.window.persist_window
+            && let Err(e) = self.config.save()
+        {
+            warn!("Could not save window geometry: {e}");
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pane_count(tree: &Tree<PluginId>, id: PluginId) -> usize {
+        tree.tiles
+            .iter()
+            .filter(|(_, tile)| matches!(tile, Tile::Pane(p) if *p == id))
+            .count()
+    }
+
+    #[test]
+    fn test_repair_drops_unknown_and_duplicate_panes() {
+        let mut tree = default_tree(&[1, 2, 3]);
+        add_pane(&mut tree, 1);
+        let known = HashSet::from([1, 3]);
+
+        assert!(validate_and_repair(&mut tree, &known));
+        assert_eq!(pane_count(&tree, 1), 1);
+        assert_eq!(pane_count(&tree, 2), 0);
+        assert_eq!(pane_count(&tree, 3), 1);
+    }
+
+    #[test]
+    fn test_repair_rejects_tree_without_known_panes() {
+        let mut tree = default_tree(&[1, 2]);
+        assert!(!validate_and_repair(&mut tree, &HashSet::new()));
+    }
+
+    #[test]
+    fn test_missing_panes_are_added_once() {
+        let mut tree = default_tree(&[1]);
+        add_missing_panes(&mut tree, [1, 2]);
+        add_missing_panes(&mut tree, [2]);
+        assert_eq!(pane_count(&tree, 1), 1);
+        assert_eq!(pane_count(&tree, 2), 1);
+    }
+}
@@ -1,25 +1,186 @@
+use std::{sync::Arc, time::Duration};
+
+use arc_swap::ArcSwap;
 use dashmap::DashMap;
 use lunaris_api::bridge::ShareableState;
+use parking_lot::Mutex;
 
 // --- Type alias for Plugin IDs ---
-pub type PluginId = usize;
+/// Derived from the plugin name with `plugin_id`, so it is the same across runs and builds.
+pub type PluginId = u64;
+
+/// Stable id for the plugin called `name` (64-bit FNV-1a of the name).
+pub const fn plugin_id(name: &str) -> PluginId {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    let bytes = name.as_bytes();
+    let mut hash = OFFSET;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(PRIME);
+        i += 1;
+    }
+    hash
+}
 
+/// Double-buffered shared state: the world thread publishes a new snapshot every tick with
+/// `store`, and the UI thread reads the latest one with `load` without taking a lock.
+pub type SharedStateHandle = Arc<ArcSwap<SharedState>>;
+
+/// Per-plugin state published to the UI.
+///
+/// `read` and `write` hand out dashmap references, and holding two at once can deadlock (two
+/// entries may share a shard lock). Code that needs several entries together should use
+/// `with_many` instead of nesting `read`/`write` calls.
 #[derive(Default)]
 pub struct SharedState {
     state: DashMap<PluginId, Box<dyn ShareableState>>,
+    world: WorldStats,
+    /// Serializes `with_many` so overlapping calls don't see each other's checked-out entries.
+    checkout: Mutex<()>,
+}
+
+/// World loop timing, published with every shared state snapshot.
+#[derive(Clone, Debug, Default)]
+pub struct WorldStats {
+    /// Ticks run since the world thread started.
+    pub ticks: u64,
+    /// Ticks that took longer than the tick interval.
+    pub overruns: u64,
+    /// Wall time of recent ticks, oldest first.
+    pub recent_ticks: Vec<Duration>,
+    /// Ticking is suspended; commands are still handled.
+    pub paused: bool,
+}
+
+impl WorldStats {
+    pub fn last_tick(&self) -> Option<Duration> {
+        self.recent_ticks.last().copied()
+    }
 }
 
 impl SharedState {
+    pub fn with_world_stats(world: WorldStats) -> Self {
+        Self {
+            state: DashMap::new(),
+            world,
+            checkout: Mutex::new(()),
+        }
+    }
+    pub fn world_stats(&self) -> &WorldStats {
+        &self.world
+    }
+    pub fn len(&self) -> usize {
+        self.state.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.state.is_empty()
+    }
+    pub fn insert(&self, id: PluginId, state: Box<dyn ShareableState>) {
+        self.state.insert(id, state);
+    }
+    /// `None` if there is no entry for `id`, or while `with_many` has it checked out.
     pub fn read<'a>(
         &'a self,
         id: PluginId,
     ) -> Option<dashmap::mapref::one::Ref<'a, PluginId, Box<dyn ShareableState>>> {
         self.state.get(&id)
     }
+    /// `None` if there is no entry for `id`, or while `with_many` has it checked out.
     pub fn write<'a>(
         &'a self,
         id: PluginId,
     ) -> Option<dashmap::mapref::one::RefMut<'a, PluginId, Box<dyn ShareableState>>> {
         self.state.get_mut(&id)
     }
+
+    /// Run `f` with mutable access to the entries for `ids`, given in the same order as `ids`
+    /// (`None` for missing ids and repeated ones). Entries are checked out of the map in
+    /// ascending id order and put back afterwards, even if `f` panics, so no map lock is held
+    /// while `f` runs and no lock-order deadlock is possible. Meanwhile `read`/`write` of
+    /// those ids from other threads return `None`.
+    pub fn with_many<R>(
+        &self,
+        ids: &[PluginId],
+        f: impl FnOnce(&mut [Option<Box<dyn ShareableState>>]) -> R,
+    ) -> R {
+        let _checkout = self.checkout.lock();
+        let mut order: Vec<usize> = (0..ids.len()).collect();
+        order.sort_by_key(|&i| ids[i]);
+        let mut entries = CheckedOut {
+            map: &self.state,
+            ids,
+            entries: (0..ids.len()).map(|_| None).collect(),
+        };
+        for i in order {
+            entries.entries[i] = self.state.remove(&ids[i]).map(|(_, state)| state);
+        }
+        f(&mut entries.entries)
+    }
+}
+
+/// Entries taken out by `SharedState::with_many`; returns them to the map when dropped.
+struct CheckedOut<'a> {
+    map: &'a DashMap<PluginId, Box<dyn ShareableState>>,
+    ids: &'a [PluginId],
+    entries: Vec<Option<Box<dyn ShareableState>>>,
+}
+
+impl Drop for CheckedOut<'_> {
+    fn drop(&mut self) {
+        for (id, entry) in self.ids.iter().zip(self.entries.drain(..)) {
+            if let Some(state) = entry {
+                self.map.insert(*id, state);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::thread;
+
+    struct Marker;
+
+    impl ShareableState for Marker {}
+
+    fn shared(ids: &[PluginId]) -> SharedState {
+        let state = SharedState::default();
+        for id in ids {
+            state.insert(*id, Box::new(Marker));
+        }
+        state
+    }
+
+    #[test]
+    fn test_with_many_hides_checked_out_entries_from_other_readers() {
+        let state = shared(&[1, 2, 3]);
+        state.with_many(&[2, 1, 1], |entries| {
+            assert!(entries[0].is_some() && entries[1].is_some());
+            // A repeated id is only handed out once.
+            assert!(entries[2].is_none());
+            thread::scope(|s| {
+                s.spawn(|| {
+                    assert!(state.read(1).is_none());
+                    assert!(state.write(2).is_none());
+                    assert!(state.read(3).is_some());
+                });
+            });
+        });
+        assert!((1..=3).all(|id| state.read(id).is_some()));
+    }
+
+    #[test]
+    fn test_with_many_restores_entries_when_f_panics() {
+        let state = shared(&[1, 2]);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            state.with_many::<()>(&[1, 2], |_| panic!("f panicked"));
+        }));
+        assert!(result.is_err());
+        assert_eq!(state.len(), 2);
+        assert!(state.read(1).is_some() && state.read(2).is_some());
+    }
 }
@@ -0,0 +1,113 @@
+//! User configuration persisted between runs as TOML.
+
+use std::{collections::BTreeSet, env, fs, io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct LunarisConfig {
+    /// Plugins the user switched off. Kept as a deny-list so newly installed plugins start out
+    /// enabled.
+    pub disabled_plugins: BTreeSet<String>,
+    pub window: WindowConfig,
+    /// Set for configs that came from a workspace file; `save` leaves the user config alone.
+    #[serde(skip)]
+    pub read_only: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct WindowConfig {
+    /// Inner size in points.
+    pub size: [f32; 2],
+    /// Outer position in points; `None` lets the window manager place the window.
+    pub position: Option<[f32; 2]>,
+    pub vsync: bool,
+    /// MSAA sample count, `0` to disable.
+    pub multisampling: u16,
+    /// Write the window geometry back to the config on exit so the next launch restores it.
+    pub persist_window: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            size: [1280.0, 720.0],
+            position: None,
+            vsync: true,
+            multisampling: 0,
+            persist_window: true,
+        }
+    }
+}
+
+impl WindowConfig {
+    pub fn native_options(&self) -> eframe::NativeOptions {
+        let mut viewport = eframe::egui::ViewportBuilder::default().with_inner_size(self.size);
+        if let Some(position) = self.position {
+            viewport = viewport.with_position(position);
+        }
+        eframe::NativeOptions {
+            viewport,
+            vsync: self.vsync,
+            multisampling: self.multisampling,
+            persist_window: self.persist_window,
+            ..Default::default()
+        }
+    }
+}
+
+impl LunarisConfig {
+    /// `LUNARIS_CONFIG` if set, otherwise `config.toml` in the app's storage directory.
+    pub fn path() -> Option<PathBuf> {
+        env::var_os("LUNARIS_CONFIG")
+            .map(PathBuf::from)
+            .or_else(|| eframe::storage_dir("Lunaris").map(|dir| dir.join("config.toml")))
+    }
+
+    /// Load the config, falling back to defaults (with a warning) if it is missing or invalid.
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+        match fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text).unwrap_or_else(|e| {
+                warn!("Ignoring malformed config {}: {e}", path.display());
+                Self::default()
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                warn!("Could not read config {}: {e}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        if self.read_only {
+            return Ok(());
+        }
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text = toml::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, text)
+    }
+
+    pub fn is_enabled(&self, plugin: &str) -> bool {
+        !self.disabled_plugins.contains(plugin)
+    }
+
+    pub fn set_enabled(&mut self, plugin: &str, enabled: bool) {
+        if enabled {
+            self.disabled_plugins.remove(plugin);
+        } else {
+            self.disabled_plugins.insert(plugin.to_string());
+        }
+    }
+}
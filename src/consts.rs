@@ -5,6 +5,14 @@ pub const HEADLESS: bool = false;
 #[cfg(feature = "headless")]
 pub const HEADLESS: bool = true;
 
+/// Whether panics unwind. With `panic = "abort"` a panicking plugin or job takes the whole
+/// process down, so any panic isolation in the runtime is inert.
+pub const PANIC_UNWIND: bool = cfg!(panic = "unwind");
+
+/// Whether the host was built with debug assertions. Plugins can use this to run extra
+/// validation only when the host does.
+pub const DEBUG_BUILD: bool = cfg!(debug_assertions);
+
 pub const TPS: u64 = 42336000000;
 
 // The rest will be created by the build script.
@@ -0,0 +1,146 @@
+//! Self-test for support tickets: checks each subsystem without opening the UI.
+
+use std::{env, fmt};
+
+use lunaris_api::plugin::GuiRegistration;
+
+use crate::{
+    consts, gpu,
+    kernel::{KernelState, Phase},
+    orchestrator::worker::SchedulerConfig,
+};
+
+/// Outcome of a single check.
+pub enum Check {
+    Ok(String),
+    Failed(String),
+    /// Not checked, e.g. because the subsystem is not the host's to initialize.
+    Skipped(String),
+}
+
+impl Check {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Check::Ok(_))
+    }
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Check::Failed(_))
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Check::Ok(detail) => write!(f, "ok ({detail})"),
+            Check::Failed(reason) => write!(f, "FAILED ({reason})"),
+            Check::Skipped(reason) => write!(f, "skipped ({reason})"),
+        }
+    }
+}
+
+pub struct DiagnosticReport {
+    pub version: &'static str,
+    /// Critical.
+    pub gpu: Check,
+    /// Critical once checked. `lunaris_api` owns the mailbox, so this is skipped unless its
+    /// owner advanced the kernel to `Phase::MailboxReady`.
+    pub mailbox: Check,
+    pub gui_plugins: usize,
+    pub default_threads: usize,
+    pub frame_threads: usize,
+    pub background_threads: usize,
+    /// `RUST_LOG` as the logger will read it, or the built-in default.
+    pub log_filter: String,
+    pub panic_unwind: bool,
+    pub debug_build: bool,
+}
+
+impl DiagnosticReport {
+    /// `true` if no critical subsystem failed to initialize.
+    pub fn is_healthy(&self) -> bool {
+        !self.gpu.is_failed() && !self.mailbox.is_failed()
+    }
+
+    /// Process exit code for a `--selftest` run: `0` if healthy, `1` otherwise.
+    pub fn exit_code(&self) -> i32 {
+        if self.is_healthy() { 0 } else { 1 }
+    }
+}
+
+impl fmt::Display for DiagnosticReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Lunaris {}", self.version)?;
+        writeln!(f, "  gpu:          {}", self.gpu)?;
+        writeln!(f, "  mailbox:      {}", self.mailbox)?;
+        writeln!(f, "  gui plugins:  {}", self.gui_plugins)?;
+        writeln!(
+            f,
+            "  workers:      default={} frame={} background={}",
+            self.default_threads, self.frame_threads, self.background_threads
+        )?;
+        writeln!(f, "  log filter:   {}", self.log_filter)?;
+        writeln!(f, "  panic unwind: {}", self.panic_unwind)?;
+        write!(f, "  debug build:  {}", self.debug_build)
+    }
+}
+
+/// Check every subsystem and report, without opening the UI or spawning the worker pool.
+/// Wrappers implementing `--selftest` print the report and exit with `exit_code()`.
+pub fn run_selftest() -> DiagnosticReport {
+    let gpu = match gpu::probe() {
+        Ok(info) => Check::Ok(format!(
+            "{} ({:?}, {:?})",
+            info.name, info.device_type, info.backend
+        )),
+        Err(e) => Check::Failed(e.to_string()),
+    };
+    let scheduler = SchedulerConfig::builder().build();
+    DiagnosticReport {
+        version: consts::VERSION_FULL,
+        gpu,
+        mailbox: mailbox_check(),
+        gui_plugins: inventory::iter::<GuiRegistration>.into_iter().count(),
+        default_threads: scheduler.default_threads,
+        frame_threads: scheduler.frame_threads,
+        background_threads: scheduler.background_threads,
+        log_filter: env::var("RUST_LOG").unwrap_or_else(|_| "info (default)".into()),
+        panic_unwind: consts::PANIC_UNWIND,
+        debug_build: consts::DEBUG_BUILD,
+    }
+}
+
+/// The host never initializes the mailbox itself, so before `Phase::MailboxReady` there is
+/// nothing to fail; a `--selftest` run without the UI always ends up here.
+fn mailbox_check() -> Check {
+    match KernelState::global().require(Phase::MailboxReady) {
+        Ok(()) => Check::Ok("initialized".into()),
+        Err(_) => Check::Skipped("owned by lunaris_api; not initialized by the host".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(gpu: Check) -> DiagnosticReport {
+        DiagnosticReport {
+            version: consts::VERSION_FULL,
+            gpu,
+            mailbox: mailbox_check(),
+            gui_plugins: 0,
+            default_threads: 1,
+            frame_threads: 1,
+            background_threads: 1,
+            log_filter: "info".into(),
+            panic_unwind: consts::PANIC_UNWIND,
+            debug_build: consts::DEBUG_BUILD,
+        }
+    }
+
+    #[test]
+    fn test_selftest_without_ui_can_be_healthy() {
+        let healthy = report(Check::Ok("test adapter".into()));
+        assert!(healthy.is_healthy());
+        assert_eq!(healthy.exit_code(), 0);
+        assert_eq!(report(Check::Failed("no adapter".into())).exit_code(), 1);
+    }
+}
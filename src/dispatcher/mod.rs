@@ -1,4 +1,15 @@
+pub mod pacing;
+
+use std::collections::VecDeque;
+use std::time::Instant;
+
 use lunaris_ecs::prelude::*;
+use tracing::{debug, error};
+
+use crate::{
+    gpu,
+    orchestrator::{Orchestrator, worker::Task},
+};
 
 #[derive(Resource)]
 pub struct DispatchReader {}
@@ -16,3 +27,73 @@ pub struct RenderNode {
     pub entity: Entity,
     pub children: Vec<Entity>,
 }
+
+/// Every tile job of one frame. Frames are handed to the orchestrator whole or not at all,
+/// since a partially submitted frame shows up as tearing in tiled output.
+pub struct FrameBatch {
+    pub frame: u64,
+    pub tiles: Vec<Task>,
+    /// When the frame has to be presented, usually `FramePacer::next_present`. A frame still
+    /// pending at its deadline would only be shown late, so it is dropped instead.
+    pub deadline: Option<Instant>,
+}
+
+/// Frames waiting to be submitted, oldest first.
+#[derive(Resource, Default)]
+pub struct PendingFrames {
+    queue: VecDeque<FrameBatch>,
+}
+
+impl PendingFrames {
+    pub fn push(&mut self, batch: FrameBatch) {
+        self.queue.push_back(batch);
+    }
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+}
+
+/// Submit pending frames in order. A frame that does not fit in the frame queue right now is
+/// deferred, together with every frame after it, to the next tick.
+/// Dispatch pauses while the GPU device is lost.
+pub fn dispatch_frames(mut pending: ResMut<PendingFrames>, orch: Res<Orchestrator>) {
+    if gpu::is_device_lost() && !gpu::try_recover() {
+        return;
+    }
+    let now = Instant::now();
+    while let Some(FrameBatch {
+        frame,
+        tiles,
+        deadline,
+    }) = pending.queue.pop_front()
+    {
+        if deadline.is_some_and(|deadline| deadline <= now) {
+            debug!("Frame {frame} missed its present deadline; dropping it.");
+            continue;
+        }
+        if tiles.len() > orch.frame_queue_capacity() {
+            error!(
+                "Frame {frame} has {} tiles but the frame queue only holds {}; dropping it.",
+                tiles.len(),
+                orch.frame_queue_capacity()
+            );
+            continue;
+        }
+        // All-or-nothing: a batch that does not fit is handed back untouched.
+        if let Err(tiles) = orch.submit_frame_batch(frame, tiles) {
+            debug!("Frame queue cannot fit frame {frame} yet; deferring to the next tick.");
+            pending.queue.push_front(FrameBatch {
+                frame,
+                tiles,
+                deadline,
+            });
+            break;
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Present-time estimation for just-in-time frame scheduling.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use lunaris_ecs::prelude::*;
+
+/// Presents remembered for the interval estimate.
+const PACER_HISTORY_LEN: usize = 32;
+
+/// Tracks when frames were presented and estimates when the next present is due, so frame
+/// work can be given a deadline instead of being submitted as fast as possible.
+///
+/// Whoever presents (the code owning the surface) calls `record_present`; producers read
+/// `next_present` and put it on their `FrameBatch`.
+#[derive(Resource)]
+pub struct FramePacer {
+    /// Intervals between recent presents, oldest first.
+    intervals: VecDeque<Duration>,
+    last_present: Option<Instant>,
+    /// Assumed interval until enough presents were seen, e.g. the display's refresh period.
+    nominal: Duration,
+}
+
+impl Default for FramePacer {
+    /// 60 Hz.
+    fn default() -> Self {
+        Self::new(Duration::from_micros(16_667))
+    }
+}
+
+impl FramePacer {
+    pub fn new(nominal: Duration) -> Self {
+        Self {
+            intervals: VecDeque::with_capacity(PACER_HISTORY_LEN),
+            last_present: None,
+            nominal,
+        }
+    }
+
+    pub fn record_present(&mut self, at: Instant) {
+        if let Some(last) = self.last_present
+            && let Some(interval) = at.checked_duration_since(last)
+        {
+            if self.intervals.len() == PACER_HISTORY_LEN {
+                self.intervals.pop_front();
+            }
+            self.intervals.push_back(interval);
+        }
+        self.last_present = Some(at);
+    }
+
+    /// Median of the recent present intervals (robust against single hitches), or the
+    /// nominal interval before any were recorded.
+    pub fn interval(&self) -> Duration {
+        if self.intervals.is_empty() {
+            return self.nominal;
+        }
+        let mut sorted: Vec<Duration> = self.intervals.iter().copied().collect();
+        sorted.sort_unstable();
+        sorted[sorted.len() / 2]
+    }
+
+    /// The first estimated present strictly after `now`, assuming presents keep following the
+    /// last recorded one at `interval`. Without any present, `now + interval`.
+    pub fn next_present(&self, now: Instant) -> Instant {
+        let interval = self.interval();
+        let Some(last) = self.last_present else {
+            return now + interval;
+        };
+        if interval.is_zero() || last > now {
+            return last.max(now + interval);
+        }
+        let missed = (now - last).as_nanos() / interval.as_nanos() + 1;
+        last + interval * missed as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_present_follows_recorded_cadence() {
+        let interval = Duration::from_millis(10);
+        let mut pacer = FramePacer::new(Duration::from_millis(50));
+        let start = Instant::now();
+        for n in 0..5 {
+            pacer.record_present(start + interval * n);
+        }
+        assert_eq!(pacer.interval(), interval);
+        let last = start + interval * 4;
+        assert_eq!(pacer.next_present(last), last + interval);
+        // Skipped presents are accounted for.
+        assert_eq!(pacer.next_present(last + interval * 2), last + interval * 3);
+    }
+}
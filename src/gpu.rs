@@ -0,0 +1,261 @@
+//! GPU device acquisition and device-loss tracking.
+
+use std::{
+    env,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use futures::executor::block_on;
+use lunaris_api::{
+    render,
+    util::error::{LunarisError, Result},
+};
+use parking_lot::RwLock;
+use tracing::{debug, error, info, warn};
+use wgpu::{
+    Adapter, AdapterInfo, Backends, Device, DeviceDescriptor, DeviceLostReason, Instance, Queue,
+    RequestAdapterOptions,
+};
+
+use crate::{
+    kernel::{KernelState, Phase},
+    oops::Oops,
+};
+
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+static DEVICE_LOST: AtomicBool = AtomicBool::new(false);
+static RECOVERY_ATTEMPTED: AtomicBool = AtomicBool::new(false);
+static SHARED: RwLock<Option<(Arc<Device>, Arc<Queue>)>> = RwLock::new(None);
+/// Index into `enumerate_adapters` chosen with `init_with`; `None` lets wgpu pick. Recovery
+/// after a device loss uses the same adapter.
+static ADAPTER: RwLock<Option<usize>> = RwLock::new(None);
+/// Info of the adapter the current device came from.
+static ADAPTER_INFO: RwLock<Option<AdapterInfo>> = RwLock::new(None);
+
+/// Acquire an adapter and device, hook device-loss reporting, and hand them to the renderer,
+/// then advance the kernel to `Phase::GpuReady`. Fails with `AlreadyExists` if the GPU is
+/// already initialized rather than replacing the renderer's device.
+pub fn init() -> Result {
+    init_on(None)
+}
+
+/// `init` on adapter `index` of `enumerate_adapters`. Fails with `InvalidArgument` if there
+/// is no such adapter and `RenderInitFailed` if it cannot provide a device.
+pub fn init_with(index: usize) -> Result {
+    init_on(Some(index))
+}
+
+fn init_on(adapter: Option<usize>) -> Result {
+    if INITIALIZED.swap(true, Ordering::AcqRel) {
+        return Err(LunarisError::AlreadyExists { item: "gpu".into() });
+    }
+    *ADAPTER.write() = adapter;
+    if let Err(e) = install() {
+        INITIALIZED.store(false, Ordering::Release);
+        return Err(e);
+    }
+    KernelState::global().advance(Phase::GpuReady);
+    Ok(())
+}
+
+/// Every adapter wgpu can see, in the order `init_with` indexes them.
+pub fn enumerate_adapters() -> Vec<AdapterInfo> {
+    Instance::default()
+        .enumerate_adapters(Backends::all())
+        .iter()
+        .map(Adapter::get_info)
+        .collect()
+}
+
+/// The adapter index requested with `--gpu <n>` (or `--gpu=<n>`), else `LUNARIS_GPU`.
+/// Fails with `InvalidArgument` if the value is not an index.
+pub fn requested_adapter() -> Result<Option<usize>> {
+    let mut args = env::args().skip(1);
+    let mut raw = None;
+    while let Some(arg) = args.next() {
+        if arg == "--gpu" {
+            raw = args.next();
+            break;
+        }
+        if let Some(value) = arg.strip_prefix("--gpu=") {
+            raw = Some(value.to_string());
+            break;
+        }
+    }
+    let Some(raw) = raw.or_else(|| env::var("LUNARIS_GPU").ok()) else {
+        return Ok(None);
+    };
+    raw.parse()
+        .map(Some)
+        .map_err(|_| LunarisError::InvalidArgument {
+            reason: format!("GPU adapter index must be a number, got {raw:?}"),
+        })
+}
+
+pub fn is_gpu_initialized() -> bool {
+    INITIALIZED.load(Ordering::Acquire)
+}
+
+/// The device and queue currently handed to the renderer, for plugins doing their own wgpu
+/// work against the same device. `None` before init and while the device is lost.
+pub fn device() -> Option<Arc<Device>> {
+    if is_device_lost() {
+        return None;
+    }
+    SHARED.read().as_ref().map(|(device, _)| device.clone())
+}
+
+pub fn queue() -> Option<Arc<Queue>> {
+    if is_device_lost() {
+        return None;
+    }
+    SHARED.read().as_ref().map(|(_, queue)| queue.clone())
+}
+
+/// The adapter the device was created on, once initialized.
+pub fn adapter_info() -> Option<AdapterInfo> {
+    ADAPTER_INFO.read().clone()
+}
+
+fn install() -> Result {
+    let (info, device, queue) = acquire()?;
+    *ADAPTER_INFO.write() = Some(info);
+    info!("Fetched GPU specifics: {device:?}, {queue:?}");
+    watch(&device);
+    let shared = (Arc::new(device.clone()), Arc::new(queue.clone()));
+    render::init_gpu(device, queue)?;
+    *SHARED.write() = Some(shared);
+    Ok(())
+}
+
+/// `true` once the device has been lost and not successfully re-acquired.
+pub fn is_device_lost() -> bool {
+    DEVICE_LOST.load(Ordering::Acquire)
+}
+
+/// Fails with `RenderDeviceLost` while the device is gone.
+pub fn ensure_device() -> Result {
+    if is_device_lost() {
+        Err(LunarisError::RenderDeviceLost)
+    } else {
+        Ok(())
+    }
+}
+
+/// Re-acquire the device after a loss. Only the first call per process tries; later calls
+/// just report whether the device is usable again.
+pub fn try_recover() -> bool {
+    if !is_device_lost() {
+        return true;
+    }
+    if RECOVERY_ATTEMPTED.swap(true, Ordering::AcqRel) {
+        return false;
+    }
+    warn!("Attempting to re-acquire the GPU device...");
+    match install() {
+        Ok(()) => {
+            DEVICE_LOST.store(false, Ordering::Release);
+            info!("GPU device re-acquired.");
+            true
+        }
+        Err(e) => {
+            error!("Failed to re-acquire the GPU device: {e}");
+            Oops::new(LunarisError::RenderDeviceLost).notify();
+            false
+        }
+    }
+}
+
+/// Drop the runtime's shared device and queue handles at the end of teardown. The renderer's
+/// own copies live in `lunaris_api`.
+pub(crate) fn release() {
+    *SHARED.write() = None;
+}
+
+/// Forget the initialized device and any recorded loss, allowing `init` and another recovery
+/// attempt.
+pub(crate) fn reset() {
+    INITIALIZED.store(false, Ordering::Release);
+    *ADAPTER.write() = None;
+    *ADAPTER_INFO.write() = None;
+    *SHARED.write() = None;
+    DEVICE_LOST.store(false, Ordering::Release);
+    RECOVERY_ATTEMPTED.store(false, Ordering::Release);
+}
+
+/// Look for a usable adapter without creating a device, for diagnostics.
+pub fn probe() -> Result<AdapterInfo> {
+    block_on(request_adapter()).map(|adapter| adapter.get_info())
+}
+
+async fn request_adapter() -> Result<Adapter> {
+    Instance::default()
+        .request_adapter(&RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            force_fallback_adapter: false,
+            compatible_surface: None,
+        })
+        .await
+        .map_err(|e| LunarisError::KernelInitFailed {
+            reason: format!("Failed to initialize the GPU instance: {e}"),
+        })
+}
+
+fn adapter_at(index: usize) -> Result<Adapter> {
+    let mut adapters = Instance::default().enumerate_adapters(Backends::all());
+    if index >= adapters.len() {
+        return Err(LunarisError::InvalidArgument {
+            reason: format!(
+                "GPU adapter {index} does not exist; {} adapters are available",
+                adapters.len()
+            ),
+        });
+    }
+    Ok(adapters.swap_remove(index))
+}
+
+fn acquire() -> Result<(AdapterInfo, Device, Queue)> {
+    let chosen = *ADAPTER.read();
+    block_on(async {
+        let Some(index) = chosen else {
+            let adapter = request_adapter().await?;
+            let (device, queue) = adapter
+                .request_device(&DeviceDescriptor::default())
+                .await
+                .map_err(|e| LunarisError::KernelInitFailed {
+                    reason: format!("Failed to fetch GPU Device: {e}"),
+                })?;
+            return Ok((adapter.get_info(), device, queue));
+        };
+        let adapter = adapter_at(index)?;
+        let info = adapter.get_info();
+        let (device, queue) = adapter
+            .request_device(&DeviceDescriptor::default())
+            .await
+            .map_err(|e| LunarisError::RenderInitFailed {
+                reason: format!(
+                    "GPU adapter {index} ({}) could not provide a device: {e}",
+                    info.name
+                ),
+            })?;
+        Ok((info, device, queue))
+    })
+}
+
+fn watch(device: &Device) {
+    device.set_device_lost_callback(|reason, message| {
+        if matches!(reason, DeviceLostReason::Destroyed) {
+            debug!("GPU device destroyed: {message}");
+            return;
+        }
+        error!("GPU device lost ({reason:?}): {message}");
+        DEVICE_LOST.store(true, Ordering::Release);
+        Oops::new(LunarisError::RenderDeviceLost).notify();
+    });
+    device.on_uncaptured_error(Box::new(|err| {
+        error!("Uncaptured GPU error: {err}");
+    }));
+}
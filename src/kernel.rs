@@ -0,0 +1,309 @@
+//! Process-wide kernel state shared by the app, the orchestrator and the signal handlers.
+
+use std::{
+    fs, io,
+    path::PathBuf,
+    sync::atomic::{AtomicU8, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use lunaris_api::util::error::{LunarisError, Result};
+use lunaris_ecs::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    bridge::SharedState, config::LunarisConfig, orchestrator::Orchestrator,
+    registry::PluginRegistry,
+};
+
+/// Initialization phases, in the order the kernel moves through them.
+/// Phases only ever advance; a later phase implies every earlier one was reached.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    Uninit = 0,
+    GpuReady = 1,
+    /// Advanced by whoever owns the mailbox once it is initialized.
+    MailboxReady = 2,
+    Running = 3,
+    ShuttingDown = 4,
+}
+
+impl Phase {
+    fn from_u8(raw: u8) -> Self {
+        match raw {
+            0 => Phase::Uninit,
+            1 => Phase::GpuReady,
+            2 => Phase::MailboxReady,
+            3 => Phase::Running,
+            _ => Phase::ShuttingDown,
+        }
+    }
+    /// Name of the resource a phase guarantees, as reported by `Uninit` errors.
+    fn resource(self) -> &'static str {
+        match self {
+            Phase::Uninit => "nothing",
+            Phase::GpuReady => "gpu",
+            Phase::MailboxReady => "mailbox",
+            Phase::Running => "kernel",
+            Phase::ShuttingDown => "shutdown",
+        }
+    }
+}
+
+pub struct KernelState {
+    phase: AtomicU8,
+}
+
+static KERNEL_STATE: KernelState = KernelState {
+    phase: AtomicU8::new(Phase::Uninit as u8),
+};
+
+impl KernelState {
+    pub fn global() -> &'static KernelState {
+        &KERNEL_STATE
+    }
+
+    pub fn phase(&self) -> Phase {
+        Phase::from_u8(self.phase.load(Ordering::Acquire))
+    }
+
+    /// Move to `to` unless the kernel is already at or past it. Returns the previous phase.
+    pub fn advance(&self, to: Phase) -> Phase {
+        Phase::from_u8(self.phase.fetch_max(to as u8, Ordering::AcqRel))
+    }
+
+    /// Fails with `Uninit` naming the missing resource if `phase` has not been reached yet.
+    pub fn require(&self, phase: Phase) -> Result {
+        if self.phase() >= phase {
+            Ok(())
+        } else {
+            Err(LunarisError::Uninit {
+                resource: phase.resource().into(),
+            })
+        }
+    }
+
+    /// Go back to `Uninit`, the only way a phase ever moves backwards.
+    fn reset(&self) {
+        self.phase.store(Phase::Uninit as u8, Ordering::Release);
+    }
+}
+
+/// Mark the start of the shutdown sequence. New work submitted after this is rejected.
+/// Returns `true` if this call initiated the shutdown.
+pub fn begin_shutdown() -> bool {
+    KernelState::global().advance(Phase::ShuttingDown) != Phase::ShuttingDown
+}
+
+pub fn is_shutting_down() -> bool {
+    KernelState::global().phase() == Phase::ShuttingDown
+}
+
+/// Fails with `ShutdownInProgress` once `begin_shutdown` has been called.
+pub fn ensure_running() -> Result {
+    if is_shutting_down() {
+        Err(LunarisError::ShutdownInProgress)
+    } else {
+        Ok(())
+    }
+}
+
+/// Reset process-wide runtime state for test isolation or a full in-process restart: the
+/// phase goes back to `Uninit`, GPU init and loss tracking are cleared, the signal hooks from
+/// `boot` are removed and logging is put back to its initial configuration. Orchestrators and
+/// plugin registries are world resources and go away with their world; the mailbox is owned
+/// by `lunaris_api` and must be re-initialized there.
+pub fn clear_all() -> Result {
+    crate::signals::unregister_hooks();
+    crate::logging::reset().map_err(|e| LunarisError::KernelInitFailed {
+        reason: format!("Could not reset logging: {e}"),
+    })?;
+    crate::gpu::reset();
+    KERNEL_STATE.reset();
+    Ok(())
+}
+
+/// What this host build and process can do, for plugins to adapt to at runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct HostCapabilities {
+    pub version: (u32, u32, u32),
+    pub headless: bool,
+    /// A GPU device is initialized and not lost.
+    pub gpu: bool,
+    /// Async jobs can be submitted to the orchestrator.
+    pub async_jobs: bool,
+    pub panic_unwind: bool,
+    pub debug_build: bool,
+}
+
+pub fn host_capabilities() -> HostCapabilities {
+    use crate::{consts, gpu};
+    HostCapabilities {
+        version: (
+            consts::VERSION_MAJOR,
+            consts::VERSION_MINOR,
+            consts::VERSION_PATCH,
+        ),
+        headless: consts::HEADLESS,
+        gpu: gpu::is_gpu_initialized() && !gpu::is_device_lost(),
+        async_jobs: true,
+        panic_unwind: consts::PANIC_UNWIND,
+        debug_build: consts::DEBUG_BUILD,
+    }
+}
+
+/// Everything support needs from a running process, gathered by `snapshot`.
+#[derive(Serialize)]
+pub struct KernelSnapshot {
+    pub version: &'static str,
+    pub phase: String,
+    pub host: HostCapabilities,
+    pub gpu: Option<GpuSnapshot>,
+    pub plugins: Vec<PluginSnapshot>,
+    pub scheduler: Option<SchedulerSnapshot>,
+    pub shared_state_entries: usize,
+    /// Oldest first.
+    pub recent_logs: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct GpuSnapshot {
+    pub name: String,
+    pub backend: String,
+    pub device_type: String,
+    pub driver: String,
+    pub driver_info: String,
+    pub lost: bool,
+}
+
+#[derive(Serialize)]
+pub struct PluginSnapshot {
+    pub name: &'static str,
+    pub version: Option<&'static str>,
+    /// Crashed and not restarted.
+    pub down: bool,
+}
+
+#[derive(Serialize)]
+pub struct SchedulerSnapshot {
+    pub load_factor: f32,
+    pub frame_queue_capacity: usize,
+    pub frame_queue_remaining: usize,
+    /// Per priority class, most urgent first; see `DetailedProfile::rejected`.
+    pub rejected: Vec<u64>,
+    pub reserved_frame_threads: usize,
+    /// Sync jobs still running after the drain, e.g. ones that hang.
+    pub inflight: Vec<InflightSnapshot>,
+}
+
+#[derive(Serialize)]
+pub struct InflightSnapshot {
+    pub label: Option<&'static str>,
+    pub thread: String,
+    pub running_ms: u128,
+}
+
+/// Gather a `KernelSnapshot`. Meant to run on the world thread between ticks, so the world
+/// is frozen while it runs; foreground orchestrator work is drained first so queue depths
+/// reflect a settled state. A drain that fails (e.g. called from a worker) is skipped.
+pub fn snapshot(world: &World, shared: Option<&SharedState>) -> KernelSnapshot {
+    use crate::{consts, gpu, logging};
+
+    let scheduler = world.get_resource::<Orchestrator>().map(|orch| {
+        let _ = orch.join_foreground();
+        let profile = orch.profile_detailed();
+        SchedulerSnapshot {
+            load_factor: orch.load_factor(),
+            frame_queue_capacity: orch.frame_queue_capacity(),
+            frame_queue_remaining: orch.frame_queue_remaining(),
+            rejected: profile.rejected.to_vec(),
+            reserved_frame_threads: profile.reserved_frame_threads,
+            inflight: orch
+                .inflight_tasks()
+                .into_iter()
+                .map(|task| InflightSnapshot {
+                    label: task.label,
+                    thread: format!("{:?}", task.thread),
+                    running_ms: task.running_for.as_millis(),
+                })
+                .collect(),
+        }
+    });
+    let plugins = world
+        .get_resource::<PluginRegistry>()
+        .map(|registry| {
+            let down: Vec<&str> = registry.down().collect();
+            registry
+                .metadata()
+                .map(|meta| PluginSnapshot {
+                    name: meta.name,
+                    version: meta.version,
+                    down: down.contains(&meta.name),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let gpu = gpu::adapter_info().map(|info| GpuSnapshot {
+        name: info.name,
+        backend: format!("{:?}", info.backend),
+        device_type: format!("{:?}", info.device_type),
+        driver: info.driver,
+        driver_info: info.driver_info,
+        lost: gpu::is_device_lost(),
+    });
+    KernelSnapshot {
+        version: consts::VERSION_FULL,
+        phase: format!("{:?}", KernelState::global().phase()),
+        host: host_capabilities(),
+        gpu,
+        plugins,
+        scheduler,
+        shared_state_entries: shared.map_or(0, SharedState::len),
+        recent_logs: logging::recent_logs(),
+    }
+}
+
+impl KernelSnapshot {
+    /// Write as pretty JSON to `snapshot-<unix seconds>.json` next to the user config (or the
+    /// working directory if there is none), returning the path.
+    pub fn write(&self) -> io::Result<PathBuf> {
+        let dir = LunarisConfig::path()
+            .and_then(|path| path.parent().map(PathBuf::from))
+            .unwrap_or_default();
+        if !dir.as_os_str().is_empty() {
+            fs::create_dir_all(&dir)?;
+        }
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        let path = dir.join(format!("snapshot-{stamp}.json"));
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(&path, json)?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A local state, so running tests in parallel never sees the global one reset under it.
+    #[test]
+    fn test_reset_allows_a_second_init() {
+        let state = KernelState {
+            phase: AtomicU8::new(Phase::Uninit as u8),
+        };
+        for _ in 0..2 {
+            assert!(state.require(Phase::Running).is_err());
+            assert_eq!(state.advance(Phase::Running), Phase::Uninit);
+            assert!(state.require(Phase::Running).is_ok());
+            // Phases never move backwards on their own.
+            assert_eq!(state.advance(Phase::GpuReady), Phase::Running);
+            assert_eq!(state.phase(), Phase::Running);
+
+            state.reset();
+            assert_eq!(state.phase(), Phase::Uninit);
+        }
+    }
+}
@@ -4,10 +4,14 @@
 #![warn(clippy::style)]
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+#[cfg(all(feature = "require-unwind", not(panic = "unwind")))]
+compile_error!(
+    "the `require-unwind` feature needs `panic = \"unwind\"`; plugin and job panic isolation does not work with `panic = \"abort\"`"
+);
+
 use colored::Colorize;
-use futures::executor::block_on;
-use lunaris_api::{render, util::error::Result};
-use lunaris_ecs::World;
+use egui_tiles::Tree;
+use lunaris_api::util::error::{LunarisError, Result};
 use mimalloc::MiMalloc;
 use tracing::*;
 
@@ -15,70 +19,123 @@ use tracing::*;
 // This ensures inventory submissions (e.g., GUI plugins like Profiler) are discovered.
 #[allow(unused_imports)]
 use linker as _;
-use wgpu::{DeviceDescriptor, Instance, RequestAdapterOptions};
 
-use crate::{app::LunarisApp, logging::init_log_global, signals::register_hooks};
+use crate::{
+    app::LunarisApp,
+    bridge::PluginId,
+    config::LunarisConfig,
+    kernel::{KernelState, Phase},
+    logging::init_log_global,
+    oops::Oops,
+    signals::register_hooks,
+    workspace::Workspace,
+};
 
 /// Things related to the main Lunaris UI and app.
 /// Everything `egui` is mainly contained in this module.
 pub mod app;
 pub mod bridge;
+pub mod config;
 pub mod consts;
+pub mod diagnostics;
 pub mod dispatcher;
+pub mod gpu;
+pub mod kernel;
 pub mod logging;
 pub mod oops;
 pub mod orchestrator;
 pub mod plugin;
 pub mod registry;
+pub mod services;
 pub mod signals;
+pub mod teardown;
+pub mod undo;
+pub mod workspace;
+
+/// For plugin libraries loaded with `PluginRegistry::load_from_path`, which have to implement
+/// the same `lunaris_api` the host was built against.
+pub use lunaris_api;
 
 #[global_allocator]
 static GLOBAL_ALLOCATOR: MiMalloc = MiMalloc;
 
-pub fn run() -> Result {
+/// A kernel that finished booting, ready to open the UI with `BootedApp::run`.
+pub struct BootedApp {
+    config: LunarisConfig,
+    /// Layout from a workspace file, used instead of the one in eframe storage.
+    layout: Option<Tree<PluginId>>,
+}
+
+/// Bring up signal hooks, the GPU and kernel state without opening the UI. Failures are
+/// returned rather than panicking, so embedders can handle them.
+pub fn boot() -> Result<BootedApp> {
     //init_log_global();
     //info!("Initialized logger.");
     warn!("Logging init is expected to be done by the wrapper application!");
     info!("Starting Lunaris...");
+    if !consts::PANIC_UNWIND {
+        warn!(
+            "Built with panic = \"abort\": a panicking plugin or job will abort the whole process."
+        );
+    }
     info!("Registering signal hooks...");
     register_hooks()?;
     info!("Done.");
     info!("Initializing app...");
     debug!("Preparing GPU resources...");
-    let (device, queue) = block_on(async {
-        let instance = Instance::default();
-        let adapter = instance
-            .request_adapter(&RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                force_fallback_adapter: false,
-                compatible_surface: None,
-            })
-            .await
-            .expect("Failed to initialize the GPU instance.");
-
-        adapter
-            .request_device(&DeviceDescriptor::default())
-            .await
-            .expect("Failed to fetch GPU Device.")
-    });
-    info!("Fetched GPU specifics: {device:?}, {queue:?}");
-    render::init_gpu(device, queue)?;
+    match gpu::requested_adapter()? {
+        Some(index) => gpu::init_with(index)?,
+        None => gpu::init()?,
+    }
     debug!("GPU resources successfully initialized!");
     debug!("Preparing ECS and runtime state...");
-    let mut world = World::new();
+    let (config, layout) = match Workspace::load() {
+        Some(workspace) => (workspace.config, workspace.layout),
+        None => (LunarisConfig::load(), None),
+    };
     debug!("ECS state ready to launch!");
+    KernelState::global().advance(Phase::Running);
     info!(
         "Finished intitialization! {}",
         "Welcome to Lunaris!".cyan().bold()
     );
-    match eframe::run_native(
-        "Lunaris",
-        eframe::NativeOptions::default(),
-        Box::new(|_cc| Ok(Box::new(LunarisApp::default()))),
-    ) {
-        Ok(o) => info!("UI Exited normally: {o:?}"),
-        Err(e) => error!("UI Failed with Error: {e}"),
-    };
-    info!("{}", "Goodbye!".cyan().bold());
-    Ok(())
+    Ok(BootedApp { config, layout })
+}
+
+impl BootedApp {
+    /// Open the UI and block until it closes. Fails with `RenderInitFailed` if the window or
+    /// its graphics context cannot be created, e.g. without a display.
+    pub fn run(self) -> Result {
+        let Self { config, layout } = self;
+        eframe::run_native(
+            "Lunaris",
+            config.window.native_options(),
+            Box::new(|cc| {
+                let app = LunarisApp::with_config(config);
+                Ok(Box::new(match layout {
+                    Some(layout) => app.with_layout(layout),
+                    None => app.restore_layout(cc.storage),
+                }))
+            }),
+        )
+        .map_err(|e| LunarisError::RenderInitFailed {
+            reason: format!("Could not open the window: {e}"),
+        })?;
+        info!("UI exited normally.");
+        info!("{}", "Goodbye!".cyan().bold());
+        Ok(())
+    }
+}
+
+/// `boot` and then `BootedApp::run`. A failure of either is shown to the user before it is
+/// returned.
+pub fn run() -> Result {
+    let booted = boot().inspect_err(|e| {
+        error!("Lunaris failed to start: {e}");
+        Oops::from_error(e).popup();
+    })?;
+    booted.run().inspect_err(|e| {
+        error!("Lunaris UI failed: {e}");
+        Oops::from_error(e).popup();
+    })
 }
@@ -1,9 +1,55 @@
+use std::collections::{BTreeMap, VecDeque};
 use std::env;
 use std::fmt;
-use std::io::IsTerminal as _;
+use std::io::{IsTerminal as _, Write as _};
+use std::path::PathBuf;
 use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-static ANSI_ENABLED: OnceLock<bool> = OnceLock::new();
+use parking_lot::Mutex;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry,
+    filter::{Directive, LevelFilter},
+    layer::Layered,
+    reload,
+};
+
+static ANSI_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// How log events are written to stdout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    /// The Lunaris line format with colors.
+    Pretty,
+    /// The Lunaris line format without escape codes, for files and pipes.
+    Plain,
+    /// One JSON object per event, including the current span list.
+    Json,
+}
+
+impl LogFormat {
+    /// `LUNARIS_LOG_FORMAT` (`pretty`, `plain` or `json`) if set, otherwise `Pretty` when
+    /// colors are enabled (see `LUNARIS_COLOR`) and `Plain` when not.
+    pub fn from_env() -> Self {
+        match env::var("LUNARIS_LOG_FORMAT").ok().as_deref() {
+            Some("pretty") => Self::Pretty,
+            Some("plain") => Self::Plain,
+            Some("json") => Self::Json,
+            other => {
+                if let Some(raw) = other {
+                    eprintln!("Ignoring invalid LUNARIS_LOG_FORMAT={raw:?}");
+                }
+                if should_enable_ansi() {
+                    Self::Pretty
+                } else {
+                    Self::Plain
+                }
+            }
+        }
+    }
+}
 
 fn should_enable_ansi() -> bool {
     // App-specific override first
@@ -27,44 +73,232 @@ fn should_enable_ansi() -> bool {
 }
 
 pub fn init_log_global() {
-    let ansi = should_enable_ansi();
-    let _ = ANSI_ENABLED.set(ansi);
+    use tracing_subscriber::prelude::*;
+
+    let (filter, filter_handle) = reload::Layer::new(build_filter());
+    let (format, format_handle) = reload::Layer::new(build_format(LogFormat::from_env()));
+    let _ = FILTER_HANDLE.set(filter_handle);
+    let _ = FORMAT_HANDLE.set(format_handle);
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(format)
+        .with(file_layer())
+        .with(RecentLogs)
+        .init();
+}
+
+/// Keeps the background writer of the log file alive; dropped by `finish`.
+static FILE_GUARD: Mutex<Option<WorkerGuard>> = Mutex::new(None);
+
+/// A layer writing plain Lunaris-format lines to a daily rolling `lunaris.<date>.log` in
+/// `LUNARIS_LOG_DIR`. `None` if the variable is unset or the directory is unusable, in which
+/// case logs only go to stdout. Call `finish` before exiting so buffered lines are written.
+fn file_layer<S>() -> Option<impl Layer<S>>
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    use tracing_subscriber::fmt::time::UtcTime;
+
+    let dir = PathBuf::from(env::var_os("LUNARIS_LOG_DIR")?);
+    let appender = std::fs::create_dir_all(&dir)
+        .map_err(|e| e.to_string())
+        .and_then(|()| {
+            RollingFileAppender::builder()
+                .rotation(Rotation::DAILY)
+                .filename_prefix("lunaris")
+                .filename_suffix("log")
+                .build(&dir)
+                .map_err(|e| e.to_string())
+        });
+    let appender = match appender {
+        Ok(appender) => appender,
+        Err(e) => {
+            eprintln!(
+                "Cannot write logs to {}: {e}; logging to stdout only.",
+                dir.display()
+            );
+            return None;
+        }
+    };
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    *FILE_GUARD.lock() = Some(guard);
+    Some(
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(writer)
+            .event_format(LunarisFormatter {
+                ansi: false,
+                timer: UtcTime::rfc_3339(),
+            }),
+    )
+}
+
+/// Events kept for `recent_logs`.
+const RECENT_LOG_LEN: usize = 256;
+static RECENT: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Keeps the last `RECENT_LOG_LEN` events that passed the filter, as plain text.
+struct RecentLogs;
+
+impl<S: tracing::Subscriber> Layer<S> for RecentLogs {
+    fn on_event(&self, event: &Event<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+        let meta = event.metadata();
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let mut line = format!("{} {}:", meta.level(), meta.target());
+        if let Some(message) = visitor.take_message() {
+            line.push(' ');
+            line.push_str(&message);
+        }
+        let kv = visitor.format_kv();
+        if !kv.is_empty() {
+            line.push(' ');
+            line.push_str(&kv);
+        }
+        let mut recent = RECENT.lock();
+        if recent.len() == RECENT_LOG_LEN {
+            recent.pop_front();
+        }
+        recent.push_back(line);
+    }
+}
+
+/// The most recent log events, oldest first. Empty unless `init_log_global` set up logging.
+pub fn recent_logs() -> Vec<String> {
+    RECENT.lock().iter().cloned().collect()
+}
+
+/// Plugins log under the target `lunaris::plugin::<name>`, e.g.
+/// `info!(target: "lunaris::plugin::blur", ...)`, so `set_plugin_level` can filter them.
+pub const PLUGIN_TARGET_PREFIX: &str = "lunaris::plugin::";
+
+/// The tracing target plugin `name` is expected to log under.
+pub fn plugin_target(name: &str) -> String {
+    format!("{PLUGIN_TARGET_PREFIX}{name}")
+}
+
+/// Levels set with `set_plugin_level`, by plugin name.
+static PLUGIN_LEVELS: Mutex<BTreeMap<String, LevelFilter>> = Mutex::new(BTreeMap::new());
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// The registry with the reloadable filter applied; the format layer sits on top of it.
+type Filtered = Layered<reload::Layer<EnvFilter, Registry>, Registry>;
+type FormatLayer = Box<dyn Layer<Filtered> + Send + Sync>;
+static FORMAT_HANDLE: OnceLock<reload::Handle<FormatLayer, Filtered>> = OnceLock::new();
+
+fn build_format(format: LogFormat) -> FormatLayer {
+    use tracing_subscriber::fmt::time::UtcTime;
+
+    let ansi = matches!(format, LogFormat::Pretty);
+    ANSI_ENABLED.store(ansi, Ordering::Release);
     // Keep `colored` output consistent with tracing's ANSI decision
     #[allow(deprecated)]
     {
         // colored 2.x uses this global override; it's a no-op if the crate version changes.
         colored::control::set_override(ansi);
     }
+    match format {
+        LogFormat::Pretty | LogFormat::Plain => tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .event_format(LunarisFormatter {
+                ansi,
+                timer: UtcTime::rfc_3339(),
+            })
+            .boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .boxed(),
+    }
+}
 
-    use tracing_subscriber::{EnvFilter, filter::Directive, fmt::time::UtcTime};
-    let formatter = LunarisFormatter {
-        ansi,
-        timer: UtcTime::rfc_3339(),
-    };
+/// Switch the output format of the running subscriber; filters and plugin levels are kept.
+/// A no-op if `init_log_global` was not used to set up logging.
+pub fn set_format(format: LogFormat) -> Result<(), reload::Error> {
+    match FORMAT_HANDLE.get() {
+        Some(handle) => handle.reload(build_format(format)),
+        None => Ok(()),
+    }
+}
 
+/// `RUST_LOG` (default `info`), with noisy GPU crates quietened and per-plugin levels on top.
+fn build_filter() -> EnvFilter {
     let mut filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
     for directive in ["wgpu_core=warn", "wgpu_hal=warn", "naga=warn"] {
         if let Ok(dir) = directive.parse::<Directive>() {
             filter = filter.add_directive(dir);
         }
     }
+    for (name, level) in PLUGIN_LEVELS.lock().iter() {
+        let target = plugin_target(name);
+        for directive in [
+            format!("{target}={level}"),
+            format!("[plugin{{name={name}}}]={level}"),
+        ] {
+            match directive.parse::<Directive>() {
+                Ok(dir) => filter = filter.add_directive(dir),
+                Err(e) => eprintln!("Invalid log directive for plugin {name}: {e}"),
+            }
+        }
+    }
+    filter
+}
 
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_ansi(false)
-        .event_format(formatter)
-        .init();
+/// Log plugin `name` at `level` or above, replacing any earlier level for it. Applies to
+/// events on `plugin_target(name)`, which `LevelFilter::OFF` silences, and raises verbosity
+/// for anything else emitted inside the plugin's `plugin` span.
+pub fn set_plugin_level(name: &str, level: LevelFilter) -> Result<(), reload::Error> {
+    PLUGIN_LEVELS.lock().insert(name.to_string(), level);
+    reload_filter()
+}
+
+/// Drop the level set with `set_plugin_level`.
+pub fn clear_plugin_level(name: &str) -> Result<(), reload::Error> {
+    PLUGIN_LEVELS.lock().remove(name);
+    reload_filter()
+}
+
+/// Rebuild the active filter. A no-op if `init_log_global` was not used to set up logging.
+fn reload_filter() -> Result<(), reload::Error> {
+    match FILTER_HANDLE.get() {
+        Some(handle) => handle.reload(build_filter()),
+        None => Ok(()),
+    }
+}
+
+/// Put the running subscriber back to its `init_log_global` state: plugin levels are
+/// dropped, the filter and format are rebuilt from the environment and the recent-events
+/// buffer is emptied. The log file, if any, is kept. For `kernel::clear_all`.
+pub fn reset() -> Result<(), reload::Error> {
+    PLUGIN_LEVELS.lock().clear();
+    RECENT.lock().clear();
+    reload_filter()?;
+    set_format(LogFormat::from_env())
+}
+
+/// Push out buffered log output. Called on the abort and exit paths, which skip destructors.
+pub fn flush() {
+    let _ = std::io::stdout().flush();
+    let _ = std::io::stderr().flush();
+}
+
+/// `flush`, then write out and close the log file. Later events only reach stdout, so call
+/// this last, right before the process ends.
+pub fn finish() {
+    flush();
+    drop(FILE_GUARD.lock().take());
 }
 
 pub fn ansi_enabled() -> bool {
-    *ANSI_ENABLED.get().unwrap_or(&false)
+    ANSI_ENABLED.load(Ordering::Acquire)
 }
 
 use colored::Colorize;
 use tracing::Event;
 use tracing_subscriber::fmt::format::Writer;
 use tracing_subscriber::fmt::time::FormatTime;
-use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, FormattedFields};
 use tracing_subscriber::registry::LookupSpan;
 
 #[derive(Clone)]
@@ -167,6 +401,12 @@ where
                     } else {
                         write!(writer, "{}", name)?;
                     }
+                    let ext = span.extensions();
+                    if let Some(fields) = ext.get::<FormattedFields<N>>()
+                        && !fields.is_empty()
+                    {
+                        write!(writer, "{{{}}}", fields)?;
+                    }
                 }
                 if self.ansi {
                     write!(writer, "{}", "]".dimmed())?;
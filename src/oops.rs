@@ -1,22 +1,66 @@
+use std::any::Any;
+
 use lunaris_api::util::error::LunarisError;
 use native_dialog::MessageDialogBuilder;
 use notify_rust::Notification;
+use tracing::error;
+
+use crate::consts::HEADLESS;
+
+/// Whether desktop notifications and modal dialogs may be shown. Always `false` in headless
+/// builds, and can be switched off at runtime with `LUNARIS_NO_DIALOGS` (e.g. on servers or CI).
+pub fn dialogs_enabled() -> bool {
+    !HEADLESS && std::env::var_os("LUNARIS_NO_DIALOGS").is_none()
+}
+
+/// The message of a panic payload, which is a `&str` or `String` for `panic!` and friends.
+pub fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic payload")
+}
 
 pub struct Oops {
-    reason: LunarisError,
+    reason: String,
 }
 
 impl Oops {
+    pub fn new(reason: LunarisError) -> Self {
+        Self {
+            reason: reason.to_string(),
+        }
+    }
+    pub fn from_error(reason: &LunarisError) -> Self {
+        Self {
+            reason: reason.to_string(),
+        }
+    }
+    /// Build from a panic payload, as returned by `JoinHandle::join` or `catch_unwind`.
+    pub fn from_panic(payload: &(dyn Any + Send)) -> Self {
+        Self {
+            reason: format!("A background thread panicked: {}", panic_message(payload)),
+        }
+    }
     pub fn notify(&self) {
+        if !dialogs_enabled() {
+            error!("{}", self.reason);
+            return;
+        }
         let _ = Notification::new()
             .summary("Lunaris errored out")
-            .body(&self.reason.to_string())
+            .body(&self.reason)
             .show();
     }
     pub fn popup(&self) {
+        if !dialogs_enabled() {
+            error!("{}", self.reason);
+            return;
+        }
         let _ = MessageDialogBuilder::default()
             .set_title("Lunaris Error")
-            .set_text(self.reason.to_string())
+            .set_text(&self.reason)
             .set_level(native_dialog::MessageLevel::Error)
             .alert()
             .show();
@@ -0,0 +1,109 @@
+//! Handles for jobs that produce a value.
+
+use std::panic::{self, AssertUnwindSafe};
+
+use crossbeam::channel::{self, Receiver, Sender, TryRecvError};
+use lunaris_api::util::error::{LunarisError, Result};
+
+use crate::oops::panic_message;
+
+/// The eventual result of a job submitted with `Orchestrator::submit_job_with_result`.
+pub struct JobHandle<R> {
+    result: Receiver<Result<R>>,
+    /// A failure seen by `try_take`, kept for `join`.
+    failed: Option<LunarisError>,
+}
+
+impl<R: Send + 'static> JobHandle<R> {
+    /// A handle plus the job that fulfils it. The job catches a panic in `f` and reports it
+    /// through the handle instead.
+    pub(crate) fn wrap<F>(f: F) -> (Self, impl FnOnce() + Send + 'static)
+    where
+        F: FnOnce() -> R + Send + 'static,
+    {
+        let (tx, rx) = channel::bounded(1);
+        let job = move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+                LunarisError::KernelPanic {
+                    reason: format!("Job panicked: {}", panic_message(payload.as_ref())),
+                }
+            });
+            let _ = tx.send(result);
+        };
+        (Self::new(rx), job)
+    }
+
+    /// A handle that resolves to `error` right away, for jobs that could not be submitted.
+    pub(crate) fn failed(error: LunarisError) -> Self {
+        let (tx, rx): (Sender<Result<R>>, _) = channel::bounded(1);
+        let _ = tx.send(Err(error));
+        Self::new(rx)
+    }
+
+    fn new(result: Receiver<Result<R>>) -> Self {
+        Self {
+            result,
+            failed: None,
+        }
+    }
+
+    /// Block until the job has run and return its value. Fails if it could not be submitted
+    /// or panicked, and with `KernelPanic` if it was dropped without running (e.g. a
+    /// `VideoFrame` job skipped by `advance_render_epoch`).
+    pub fn join(self) -> Result<R> {
+        if let Some(e) = self.failed {
+            return Err(e);
+        }
+        self.result.recv().unwrap_or_else(|_| Err(dropped()))
+    }
+
+    /// The value if the job has finished successfully, without blocking. A failure is kept
+    /// for `join` to report.
+    pub fn try_take(&mut self) -> Option<R> {
+        if self.failed.is_some() {
+            return None;
+        }
+        match self.result.try_recv() {
+            Ok(Ok(value)) => Some(value),
+            Ok(Err(e)) => {
+                self.failed = Some(e);
+                None
+            }
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                self.failed = Some(dropped());
+                None
+            }
+        }
+    }
+}
+
+fn dropped() -> LunarisError {
+    LunarisError::KernelPanic {
+        reason: "Job was dropped before it produced a result".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_reports_value_panic_and_drop() {
+        let (mut handle, job) = JobHandle::wrap(|| 6 * 7);
+        assert_eq!(handle.try_take(), None);
+        job();
+        assert_eq!(handle.try_take(), Some(42));
+
+        let (handle, job) = JobHandle::<()>::wrap(|| panic!("boom"));
+        job();
+        assert!(matches!(
+            handle.join(),
+            Err(LunarisError::KernelPanic { .. })
+        ));
+
+        let (handle, job) = JobHandle::wrap(|| 1);
+        drop(job);
+        assert!(handle.join().is_err());
+    }
+}
@@ -0,0 +1,114 @@
+//! Fixed-interval sampling of `OrchestratorProfile` for time-series views such as the Profiler.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crossbeam::channel::{self, RecvTimeoutError};
+use lunaris_api::request::{Job, OrchestratorProfile, Priority};
+use parking_lot::Mutex;
+
+use super::worker::WorkerPool;
+
+/// Samples kept by default: one minute at the default interval.
+pub const DEFAULT_HISTORY_LEN: usize = 600;
+pub const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone)]
+pub struct TimedProfile {
+    pub at: Instant,
+    pub profile: OrchestratorProfile,
+}
+
+/// Paces profile sampling. A small timer thread submits one `Background` job per interval,
+/// and that job takes the sample, so sampling runs on the background workers and never
+/// competes with foreground work. A tick is skipped while the previous sample is still queued.
+pub(crate) struct ProfileSampler {
+    samples: Arc<Mutex<VecDeque<TimedProfile>>>,
+    /// Dropping this disconnects the channel, which stops the timer thread immediately.
+    stop: Option<channel::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ProfileSampler {
+    pub(crate) fn spawn(pool: &Arc<WorkerPool>, interval: Duration, capacity: usize) -> Self {
+        let samples = Arc::new(Mutex::new(VecDeque::with_capacity(capacity)));
+        let (stop, stopped) = channel::bounded::<()>(0);
+        let ring = samples.clone();
+        // Weak, so queued sample jobs do not keep the pool they run on alive.
+        let pool = Arc::downgrade(pool);
+        let pending = Arc::new(AtomicBool::new(false));
+        let thread = thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                let Some(scheduler) = pool.upgrade() else {
+                    return;
+                };
+                if pending.swap(true, Ordering::AcqRel) {
+                    continue;
+                }
+                let (weak, ring, done) = (pool.clone(), ring.clone(), pending.clone());
+                let job = Job::new(move || {
+                    if let Some(scheduler) = weak.upgrade() {
+                        let sample = TimedProfile {
+                            at: Instant::now(),
+                            profile: scheduler.profile(),
+                        };
+                        let mut ring = ring.lock();
+                        if ring.len() == capacity.max(1) {
+                            ring.pop_front();
+                        }
+                        ring.push_back(sample);
+                    }
+                    done.store(false, Ordering::Release);
+                })
+                .with_priority(Priority::Background);
+                if scheduler.add_job(job).is_err() {
+                    pending.store(false, Ordering::Release);
+                }
+            }
+        });
+        Self {
+            samples,
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+
+    /// Samples oldest first.
+    pub(crate) fn recent(&self) -> Vec<TimedProfile> {
+        self.samples.lock().iter().cloned().collect()
+    }
+}
+
+impl Drop for ProfileSampler {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::worker::SchedulerConfig;
+
+    #[test]
+    fn test_sampler_records_profiles_from_background_jobs() {
+        let pool = Arc::new(WorkerPool::new(SchedulerConfig::balanced(2)));
+        let sampler = ProfileSampler::spawn(&pool, Duration::from_millis(1), 4);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while sampler.recent().len() < 4 {
+            assert!(Instant::now() < deadline, "no profiles were sampled");
+            thread::sleep(Duration::from_millis(1));
+        }
+        // The ring keeps only the newest `capacity` samples.
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(sampler.recent().len(), 4);
+        drop(sampler);
+        pool.join_all().unwrap();
+    }
+}
@@ -0,0 +1,80 @@
+//! Scheduling latency histograms recorded by the worker pool.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Number of log2 buckets; bucket `i` holds samples in `[2^i, 2^(i+1))` microseconds
+/// (bucket 0 also takes sub-microsecond samples, the last bucket everything above it).
+pub const HISTOGRAM_BUCKETS: usize = 32;
+
+/// Lock-free bucketed latency histogram.
+#[derive(Default)]
+pub struct LatencyHistogram {
+    buckets: [AtomicU64; HISTOGRAM_BUCKETS],
+}
+
+impl LatencyHistogram {
+    pub fn record(&self, elapsed: Duration) {
+        let micros = elapsed.as_micros().min(u64::MAX as u128) as u64;
+        let bucket = (u64::BITS - micros.leading_zeros()).saturating_sub(1) as usize;
+        self.buckets[bucket.min(HISTOGRAM_BUCKETS - 1)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        HistogramSnapshot {
+            buckets: std::array::from_fn(|i| self.buckets[i].load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// Point-in-time copy of a `LatencyHistogram`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HistogramSnapshot {
+    pub buckets: [u64; HISTOGRAM_BUCKETS],
+}
+
+impl HistogramSnapshot {
+    pub fn count(&self) -> u64 {
+        self.buckets.iter().sum()
+    }
+
+    /// Upper bound of the bucket containing the `quantile` (0.0..=1.0) sample, or `None`
+    /// if nothing was recorded. Resolution is a factor of two.
+    pub fn percentile(&self, quantile: f64) -> Option<Duration> {
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+        let rank = ((quantile.clamp(0.0, 1.0) * count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (i, n) in self.buckets.iter().enumerate() {
+            seen += n;
+            if seen >= rank {
+                return Some(Duration::from_micros(2u64 << i));
+            }
+        }
+        None
+    }
+}
+
+/// Wait-time (enqueue → start) and run-time (start → finish) for one priority class.
+#[derive(Default)]
+pub struct TaskTimings {
+    pub wait: LatencyHistogram,
+    pub run: LatencyHistogram,
+}
+
+impl TaskTimings {
+    pub fn snapshot(&self) -> TimingSnapshot {
+        TimingSnapshot {
+            wait: self.wait.snapshot(),
+            run: self.run.snapshot(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TimingSnapshot {
+    pub wait: HistogramSnapshot,
+    pub run: HistogramSnapshot,
+}
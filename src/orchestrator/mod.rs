@@ -1,3 +1,8 @@
+pub mod handle;
+pub mod history;
+pub mod metrics;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod worker;
 
 use futures::FutureExt;
@@ -23,28 +28,43 @@ use futures::future::BoxFuture;
 use lunaris_api::request::{AsyncJob, DynOrchestrator, Job, Priority};
 use lunaris_api::util::error::Result;
 use lunaris_ecs::Resource;
+use tracing::debug;
 
-use self::worker::{SchedulerConfig, WorkerPool};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use self::handle::JobHandle;
+use self::history::{DEFAULT_HISTORY_LEN, ProfileSampler, TimedProfile};
+use self::worker::{DetailedProfile, Placement, SchedulerConfig, Task, TaskInfo, WorkerPool};
+use crate::kernel;
 
 #[derive(Resource)]
 pub struct Orchestrator {
-    scheduler: WorkerPool,
+    // Declared first so the sampling thread stops before the pool it samples is released.
+    sampler: OnceLock<ProfileSampler>,
+    scheduler: Arc<WorkerPool>,
 }
 
 impl Default for Orchestrator {
+    /// `SchedulerConfig::balanced` sized by `worker::parallelism`, which honors `LUNARIS_THREADS`.
     fn default() -> Self {
-        let parallelism = std::thread::available_parallelism()
-            .map(|n| n.get())
-            .unwrap_or(4);
-        let cfg = SchedulerConfig::balanced(parallelism);
-        Orchestrator {
-            scheduler: WorkerPool::new(cfg),
-        }
+        Self::with_config(SchedulerConfig::balanced(worker::parallelism()))
     }
 }
 
 impl Orchestrator {
+    pub fn with_config(cfg: SchedulerConfig) -> Self {
+        debug!(
+            "Orchestrator: {} default, {} frame, {} background worker threads; async runtime {:?}.",
+            cfg.default_threads, cfg.frame_threads, cfg.background_threads, cfg.async_runtime
+        );
+        Orchestrator {
+            sampler: OnceLock::new(),
+            scheduler: Arc::new(WorkerPool::new(cfg)),
+        }
+    }
     pub fn submit_job<T: FnOnce() + Send + 'static>(&self, job: Job<T>) -> Result {
+        kernel::ensure_running()?;
         self.scheduler.add_job(job)
     }
     pub fn submit_async<F, Fut>(&self, job: AsyncJob<F, Fut>) -> Result
@@ -52,19 +72,172 @@ impl Orchestrator {
         F: FnOnce() -> Fut + Send + 'static,
         Fut: core::future::Future<Output = ()> + Send + 'static,
     {
+        kernel::ensure_running()?;
         self.scheduler.add_job_async(job)
     }
+    /// Run `f` as a job at `priority` and hand back its return value through a `JobHandle`.
+    /// Submission errors and panics in `f` surface from `JobHandle::join`.
+    pub fn submit_job_with_result<R, F>(&self, f: F, priority: Priority) -> JobHandle<R>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (handle, job) = JobHandle::wrap(f);
+        match self.submit_job(Job {
+            inner: job,
+            priority,
+        }) {
+            Ok(()) => handle,
+            Err(e) => JobHandle::failed(e),
+        }
+    }
+    /// `submit_job` with a label shown by `inflight_tasks` while the job runs.
+    pub fn submit_job_labeled<T: FnOnce() + Send + 'static>(
+        &self,
+        job: Job<T>,
+        label: &'static str,
+    ) -> Result {
+        kernel::ensure_running()?;
+        self.scheduler.add_job_labeled(job, label)
+    }
+    /// `submit_job`, promoting a `Normal`/`Deferred` job to `Immediate` if it is still queued
+    /// at `deadline`.
+    pub fn submit_job_with_deadline<T: FnOnce() + Send + 'static>(
+        &self,
+        job: Job<T>,
+        deadline: Instant,
+    ) -> Result {
+        kernel::ensure_running()?;
+        self.scheduler.add_job_with_deadline(job, deadline)
+    }
+    /// `submit_job`, but a full frame queue is awaited instead of rejected; see
+    /// `WorkerPool::add_job_awaitable`.
+    pub async fn submit_job_awaitable<T: FnOnce() + Send + 'static>(&self, job: Job<T>) -> Result {
+        kernel::ensure_running()?;
+        self.scheduler.add_job_awaitable(job).await
+    }
+    /// `submit_job` with an explicit `Placement`.
+    pub fn submit_job_placed<T: FnOnce() + Send + 'static>(
+        &self,
+        job: Job<T>,
+        placement: Placement,
+    ) -> Result {
+        kernel::ensure_running()?;
+        self.scheduler.add_job_placed(job, placement)
+    }
+    /// `submit_async` with an explicit `Placement`.
+    pub fn submit_async_placed<F, Fut>(&self, job: AsyncJob<F, Fut>, placement: Placement) -> Result
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: core::future::Future<Output = ()> + Send + 'static,
+    {
+        kernel::ensure_running()?;
+        self.scheduler.add_job_async_placed(job, placement)
+    }
+    /// Submit all tiles of a frame as `VideoFrame` jobs atomically. If the frame queue cannot
+    /// take the whole batch (or the kernel is shutting down) the tiles are handed back untouched.
+    pub fn submit_frame_batch(
+        &self,
+        frame: u64,
+        tiles: Vec<Task>,
+    ) -> std::result::Result<(), Vec<Task>> {
+        if kernel::is_shutting_down() {
+            return Err(tiles);
+        }
+        self.scheduler.add_frame_batch(frame, tiles)
+    }
+    /// Wait until every tile of `frame` has finished, e.g. before reading the frame back.
+    pub fn barrier_frame(&self, frame: u64) -> Result {
+        self.scheduler.barrier_frame(frame)
+    }
+    /// Another handle to the same worker pool, without the profile sampler. Used by the UI
+    /// thread so plugin UI code submits to the world's pool instead of a throwaway one.
+    pub(crate) fn share(&self) -> Orchestrator {
+        Orchestrator {
+            sampler: OnceLock::new(),
+            scheduler: self.scheduler.clone(),
+        }
+    }
+    /// Wait for all outstanding work, then stop the sampler and worker threads. Used by the
+    /// explicit teardown sequence; dropping an `Orchestrator` stops threads without joining.
+    /// Workers stop even while `share`d handles remain; their thread handles are joined when
+    /// the last one drops.
+    pub fn shutdown(self) -> Result {
+        let joined = self.join_all();
+        self.scheduler.stop_workers();
+        drop(self);
+        joined
+    }
     pub fn join_foreground(&self) -> Result {
         self.scheduler.join_sync()
     }
+    /// Wait only for jobs at `min` priority or above; see `WorkerPool::join_priority`.
+    pub fn join_priority(&self, min: Priority) -> Result {
+        self.scheduler.join_priority(min)
+    }
+    /// Wait for async jobs only; sync jobs are not waited on.
+    pub fn join_async(&self) -> Result {
+        self.scheduler.join_async()
+    }
+    pub fn join_async_timeout(&self, timeout: std::time::Duration) -> Result {
+        self.scheduler.join_async_timeout(timeout)
+    }
     /// Not reccomended. bg threads don't have an obligation to join.
     pub fn join_all(&self) -> Result {
         self.scheduler.join_all()
     }
-    /// reconfigure amount of threads available at runtime
-    pub fn set_threads(&self, default: usize, frame: usize, background: usize) {
+    /// Scheduler saturation: `0.0` idle, `1.0` fully busy, higher means a backlog.
+    /// Intended for adaptive quality decisions, e.g. lowering sample counts when above `1.0`.
+    pub fn load_factor(&self) -> f32 {
+        self.scheduler.load_factor()
+    }
+    /// Start recording `profile()` every `interval` as a `Background` job, keeping the last
+    /// `DEFAULT_HISTORY_LEN` samples. Later calls are no-ops.
+    pub fn start_profile_sampling(&self, interval: Duration) {
+        self.sampler
+            .get_or_init(|| ProfileSampler::spawn(&self.scheduler, interval, DEFAULT_HISTORY_LEN));
+    }
+    /// Recorded profiles, oldest first. Empty until `start_profile_sampling` is called.
+    pub fn recent_profiles(&self) -> Vec<TimedProfile> {
+        self.sampler
+            .get()
+            .map(ProfileSampler::recent)
+            .unwrap_or_default()
+    }
+    /// Sync jobs running right now, longest-running first; see `WorkerPool::inflight_tasks`.
+    pub fn inflight_tasks(&self) -> Vec<TaskInfo> {
+        self.scheduler.inflight_tasks()
+    }
+    /// Queue depths plus per-priority scheduling latency histograms.
+    pub fn profile_detailed(&self) -> DetailedProfile {
+        self.scheduler.profile_detailed()
+    }
+    /// Capacity of the frame queue. Compare against `profile().frame` to get queue fullness.
+    pub fn frame_queue_capacity(&self) -> usize {
+        self.scheduler.frame_queue_capacity()
+    }
+    /// Free slots left in the frame queue, read without locking. See
+    /// `WorkerPool::frame_queue_remaining`.
+    pub fn frame_queue_remaining(&self) -> usize {
+        self.scheduler.frame_queue_remaining()
+    }
+    /// Cancel every outstanding frame job, e.g. when a different project is loaded.
+    pub fn advance_render_epoch(&self) -> u64 {
+        self.scheduler.advance_render_epoch()
+    }
+    pub fn render_epoch(&self) -> u64 {
+        self.scheduler.render_epoch()
+    }
+    /// reconfigure amount of threads available at runtime, including the async runtime's
+    pub fn set_threads(
+        &self,
+        default: usize,
+        frame: usize,
+        background: usize,
+        async_threads: usize,
+    ) {
         self.scheduler
-            .reconfigure_threads(default, frame, background)
+            .reconfigure_threads(default, frame, background, async_threads)
     }
 }
 
@@ -90,7 +263,9 @@ impl DynOrchestrator for Orchestrator {
         Orchestrator::join_foreground(self)
     }
     fn set_threads(&self, default: usize, frame: usize, background: usize) {
-        Orchestrator::set_threads(self, default, frame, background)
+        // The trait has no async thread count; keep the current one.
+        let async_threads = self.scheduler.async_threads();
+        Orchestrator::set_threads(self, default, frame, background, async_threads)
     }
     fn profile(&self) -> lunaris_api::request::OrchestratorProfile {
         self.scheduler.profile()
@@ -0,0 +1,132 @@
+//! A thread-free `DynOrchestrator` for plugin tests.
+
+use futures::{executor::block_on, future::BoxFuture};
+use lunaris_api::request::{DynOrchestrator, OrchestratorProfile, Priority};
+use lunaris_api::util::error::Result;
+use parking_lot::Mutex;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JobKind {
+    Sync,
+    Async,
+}
+
+/// One job handed to an `InlineOrchestrator`, in submission order.
+pub struct SubmittedJob {
+    pub kind: JobKind,
+    pub priority: Priority,
+}
+
+/// Runs every submitted job to completion on the calling thread before returning, and
+/// records it. Async jobs are driven with `block_on`, so they must not wait on a Tokio reactor.
+#[derive(Default)]
+pub struct InlineOrchestrator {
+    submitted: Mutex<Vec<SubmittedJob>>,
+}
+
+impl InlineOrchestrator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of jobs submitted so far.
+    pub fn len(&self) -> usize {
+        self.submitted.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.submitted.lock().is_empty()
+    }
+
+    /// Jobs submitted since the last call, oldest first.
+    pub fn take_submitted(&self) -> Vec<SubmittedJob> {
+        std::mem::take(&mut *self.submitted.lock())
+    }
+
+    /// How many recorded jobs match `pred`,
+    /// e.g. `count(|j| matches!(j.priority, Priority::Immediate))`.
+    pub fn count(&self, pred: impl Fn(&SubmittedJob) -> bool) -> usize {
+        self.submitted.lock().iter().filter(|j| pred(j)).count()
+    }
+
+    fn record(&self, kind: JobKind, priority: Priority) {
+        self.submitted.lock().push(SubmittedJob { kind, priority });
+    }
+}
+
+impl DynOrchestrator for InlineOrchestrator {
+    fn submit_job_boxed(
+        &self,
+        job: Box<dyn FnOnce() + Send + 'static>,
+        priority: Priority,
+    ) -> Result {
+        // Recorded before running so jobs submitted from inside `job` come after it.
+        self.record(JobKind::Sync, priority);
+        job();
+        Ok(())
+    }
+    fn submit_async_boxed(&self, fut: BoxFuture<'static, ()>, priority: Priority) -> Result {
+        self.record(JobKind::Async, priority);
+        block_on(fut);
+        Ok(())
+    }
+    fn join_foreground(&self) -> Result {
+        Ok(())
+    }
+    fn set_threads(&self, _default: usize, _frame: usize, _background: usize) {}
+    fn profile(&self) -> OrchestratorProfile {
+        OrchestratorProfile {
+            immediate: 0,
+            normal: 0,
+            deferred: 0,
+            frame: 0,
+            running_tasks: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_inline_orchestrator_runs_and_records_jobs() {
+        let orch = Arc::new(InlineOrchestrator::new());
+        let ran = Arc::new(AtomicUsize::new(0));
+
+        let (inner, r) = (orch.clone(), ran.clone());
+        orch.submit_job_boxed(
+            Box::new(move || {
+                r.fetch_add(1, Ordering::Relaxed);
+                let r = r.clone();
+                inner
+                    .submit_job_boxed(
+                        Box::new(move || {
+                            r.fetch_add(1, Ordering::Relaxed);
+                        }),
+                        Priority::Deferred,
+                    )
+                    .unwrap();
+            }),
+            Priority::Immediate,
+        )
+        .unwrap();
+        let r = ran.clone();
+        orch.submit_async_boxed(
+            Box::pin(async move {
+                r.fetch_add(1, Ordering::Relaxed);
+            }),
+            Priority::Normal,
+        )
+        .unwrap();
+
+        // Everything ran before its submit call returned.
+        assert_eq!(ran.load(Ordering::Relaxed), 3);
+        assert_eq!(orch.count(|j| matches!(j.priority, Priority::Immediate)), 1);
+        let kinds: Vec<_> = orch.take_submitted().iter().map(|j| j.kind).collect();
+        assert_eq!(kinds, [JobKind::Sync, JobKind::Sync, JobKind::Async]);
+        assert!(orch.is_empty());
+    }
+}
@@ -16,59 +16,171 @@ Planned edits and design notes (2025-09):
   - add_job_async(AsyncJob<F, Fut>) for async closures
   - join_sync() waits for foreground tasks to complete
   - join_all() waits for both foreground and background tasks
-  - reconfigure_threads(default, frame, background)
+  - reconfigure_threads(default, frame, background, async_threads)
 */
 
-use parking_lot::{Condvar, Mutex};
-use std::collections::VecDeque;
+use parking_lot::{Condvar, Mutex, RwLock};
+use std::cell::Cell;
+use std::collections::{HashMap, VecDeque};
 use std::sync::{
     Arc,
-    atomic::{AtomicBool, AtomicU64, Ordering},
+    atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
 };
-use std::thread::{self, JoinHandle};
+use std::thread::{self, JoinHandle, ThreadId};
+use std::time::{Duration, Instant};
 
 use lunaris_api::request::{AsyncJob, Job, OrchestratorProfile, Priority};
 use lunaris_api::util::error::LunarisError;
 use lunaris_api::util::error::Result;
 
 use crossbeam_queue::ArrayQueue;
+use tokio::sync::{Notify, Semaphore};
+use tracing::{error, warn};
 
-type Task = Box<dyn FnOnce() + Send + 'static>;
+use super::metrics::{TaskTimings, TimingSnapshot};
 
-const FRAME_QUEUE_CAPACITY: usize = 1024;
+pub type Task = Box<dyn FnOnce() + Send + 'static>;
+
+/// Default number of slots in the bounded frame queue.
+pub const FRAME_QUEUE_CAPACITY: usize = 1024;
+/// Default for `SchedulerConfig::max_async_in_flight`.
+pub const MAX_ASYNC_IN_FLIGHT: usize = 256;
+/// Default for `SchedulerConfig::aging_threshold`.
+pub const AGING_THRESHOLD: u32 = 16;
+
+/// Lengths of the default sub-queues, mirrored into atomics so `profile()` can read them
+/// without taking the queue lock the default workers contend on.
+#[derive(Default)]
+struct QueueDepths {
+    immediate: AtomicU64,
+    normal: AtomicU64,
+    deferred: AtomicU64,
+}
 
 struct PriorityQueues {
-    immediate: VecDeque<Task>,
-    normal: VecDeque<Task>,
-    deferred: VecDeque<Task>,
+    immediate: VecDeque<QueuedTask>,
+    normal: VecDeque<QueuedTask>,
+    deferred: VecDeque<QueuedTask>,
+    depths: Arc<QueueDepths>,
+    /// Earliest deadline among queued normal/deferred tasks, so `pop` only scans for tasks to
+    /// promote once one is actually due.
+    next_deadline: Option<Instant>,
+    next_seq: u64,
+    /// Consecutive pops that passed over waiting lower-priority work; see `aging_threshold`.
+    bypassed: u32,
+    aging_threshold: u32,
+}
+
+struct QueuedTask {
+    task: Task,
+    deadline: Option<Instant>,
+    /// Enqueue order across all three queues, so aging can pick the oldest waiting task.
+    seq: u64,
 }
 
 impl PriorityQueues {
-    fn new() -> Self {
+    fn new(depths: Arc<QueueDepths>, aging_threshold: u32) -> Self {
         Self {
             immediate: VecDeque::new(),
             normal: VecDeque::new(),
             deferred: VecDeque::new(),
+            depths,
+            next_deadline: None,
+            next_seq: 0,
+            bypassed: 0,
+            aging_threshold: aging_threshold.max(1),
         }
     }
-    fn push(&mut self, p: Priority, task: Task) {
-        match p {
-            Priority::Immediate => self.immediate.push_back(task),
-            Priority::Normal => self.normal.push_back(task),
-            Priority::Deferred => self.deferred.push_back(task),
+    fn push(&mut self, p: Priority, task: Task, deadline: Option<Instant>) {
+        let (queue, depth) = match p {
+            Priority::Immediate => (&mut self.immediate, &self.depths.immediate),
+            Priority::Normal => (&mut self.normal, &self.depths.normal),
+            Priority::Deferred => (&mut self.deferred, &self.depths.deferred),
             Priority::VideoFrame => {
                 unreachable!("VideoFrame tasks are enqueued on the dedicated frame queue")
             }
             Priority::Background => {
                 unreachable!("Background tasks are enqueued on the background queue")
             }
+        };
+        if let Some(deadline) = deadline {
+            self.next_deadline = Some(self.next_deadline.map_or(deadline, |d| d.min(deadline)));
         }
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        queue.push_back(QueuedTask {
+            task,
+            deadline,
+            seq,
+        });
+        depth.fetch_add(1, Ordering::Relaxed);
     }
+    /// Pop in priority order, except that after `aging_threshold` consecutive pops passed over
+    /// waiting normal/deferred work, the oldest of those is dispatched instead.
     fn pop(&mut self) -> Option<Task> {
-        self.immediate
-            .pop_front()
-            .or_else(|| self.normal.pop_front())
-            .or_else(|| self.deferred.pop_front())
+        self.promote_due();
+        let front = |queue: &VecDeque<QueuedTask>| queue.front().map(|queued| queued.seq);
+        // 1 = normal, 2 = deferred: whichever front task was enqueued first.
+        let oldest_lower = match (front(&self.normal), front(&self.deferred)) {
+            (Some(normal), Some(deferred)) if deferred < normal => Some(2),
+            (Some(_), _) => Some(1),
+            (None, Some(_)) => Some(2),
+            (None, None) => None,
+        };
+        let aged = self.bypassed >= self.aging_threshold && oldest_lower.is_some();
+        let level = match oldest_lower {
+            Some(lower) if aged => lower,
+            _ if !self.immediate.is_empty() => 0,
+            _ if !self.normal.is_empty() => 1,
+            _ => 2,
+        };
+        let (queue, depth) = match level {
+            0 => (&mut self.immediate, &self.depths.immediate),
+            1 => (&mut self.normal, &self.depths.normal),
+            _ => (&mut self.deferred, &self.depths.deferred),
+        };
+        let queued = queue.pop_front()?;
+        depth.fetch_sub(1, Ordering::Relaxed);
+        let passed_over = match level {
+            _ if aged => false,
+            0 => oldest_lower.is_some(),
+            1 => !self.deferred.is_empty(),
+            _ => false,
+        };
+        self.bypassed = if passed_over { self.bypassed + 1 } else { 0 };
+        Some(queued.task)
+    }
+    /// Move every normal/deferred task whose deadline has passed to the back of the immediate
+    /// queue, keeping the relative order of everything else.
+    fn promote_due(&mut self) {
+        let now = Instant::now();
+        if self.next_deadline.is_none_or(|d| d > now) {
+            return;
+        }
+        let mut next_deadline = None;
+        for (queue, depth) in [
+            (&mut self.normal, &self.depths.normal),
+            (&mut self.deferred, &self.depths.deferred),
+        ] {
+            let mut kept = VecDeque::with_capacity(queue.len());
+            for queued in queue.drain(..) {
+                match queued.deadline {
+                    Some(deadline) if deadline <= now => {
+                        depth.fetch_sub(1, Ordering::Relaxed);
+                        self.depths.immediate.fetch_add(1, Ordering::Relaxed);
+                        self.immediate.push_back(queued);
+                    }
+                    Some(deadline) => {
+                        let earliest = next_deadline.get_or_insert(deadline);
+                        *earliest = (*earliest).min(deadline);
+                        kept.push_back(queued);
+                    }
+                    None => kept.push_back(queued),
+                }
+            }
+            *queue = kept;
+        }
+        self.next_deadline = next_deadline;
     }
     fn is_empty(&self) -> bool {
         self.immediate.is_empty() && self.normal.is_empty() && self.deferred.is_empty()
@@ -87,6 +199,37 @@ impl<T> CondVarQueue<T> {
             cv: Condvar::new(),
         }
     }
+
+    /// Take the next item with `pop`, parking while there is none. `stopping` and the queue are
+    /// both checked under the lock, so neither a push nor a stop request can slip in between
+    /// the check and the wait. Returns `None` once `stopping` is set.
+    fn next<R>(
+        &self,
+        stopping: &AtomicBool,
+        mut pop: impl FnMut(&mut T) -> Option<R>,
+    ) -> Option<R> {
+        let mut guard = self.queue.lock();
+        loop {
+            if stopping.load(Ordering::Acquire) {
+                return None;
+            }
+            if let Some(item) = pop(&mut guard) {
+                return Some(item);
+            }
+            self.cv.wait(&mut guard);
+        }
+    }
+
+    fn wake_one(&self) {
+        let _g = self.queue.lock();
+        self.cv.notify_one();
+    }
+
+    /// Wake every parked worker, e.g. to observe `stopping`.
+    fn wake_all(&self) {
+        let _g = self.queue.lock();
+        self.cv.notify_all();
+    }
 }
 
 struct BlockingArrayQueue<T> {
@@ -103,13 +246,88 @@ impl<T> BlockingArrayQueue<T> {
             lock: Mutex::new(()),
         }
     }
+
+    /// Pop without locking if possible, otherwise park until an item or a stop request arrives.
+    /// An empty queue always parks; returns `None` once `stopping` is set.
+    fn next(&self, stopping: &AtomicBool) -> Option<T> {
+        if stopping.load(Ordering::Acquire) {
+            return None;
+        }
+        if let Some(item) = self.q.pop() {
+            return Some(item);
+        }
+        let mut guard = self.lock.lock();
+        loop {
+            if stopping.load(Ordering::Acquire) {
+                return None;
+            }
+            if let Some(item) = self.q.pop() {
+                return Some(item);
+            }
+            self.cv.wait(&mut guard);
+        }
+    }
+
+    /// Wake one parked worker after a push. Pushes don't take `lock`, so it is acquired here:
+    /// a worker that saw the queue empty under the lock is then guaranteed to be waiting, and
+    /// the notification cannot be lost.
+    fn notify_one(&self) {
+        drop(self.lock.lock());
+        self.cv.notify_one();
+    }
+
+    fn notify_all(&self) {
+        drop(self.lock.lock());
+        self.cv.notify_all();
+    }
+}
+
+/// Where a job runs, independent of whether it was written as a closure or a future.
+///
+/// Priority still counts for joins and profiles wherever a job lands, but jobs moved onto
+/// Tokio's blocking pool are not ordered by priority there.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Placement {
+    /// Sync jobs go to the worker pool, async jobs to the Tokio runtime.
+    #[default]
+    Auto,
+    /// CPU-bound work. An async job is driven on Tokio's blocking pool so it cannot stall the
+    /// reactor; a sync job behaves as with `Auto`.
+    Cpu,
+    /// Blocking I/O. A sync job runs on Tokio's blocking pool instead of occupying a worker;
+    /// an async job behaves as with `Auto`.
+    Io,
+}
+
+/// Flavor of the Tokio runtime that executes async jobs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuntimeKind {
+    /// A single dedicated thread drives every async job, in spawn order.
+    /// Cheap for headless/CI runs and deterministic for tests.
+    CurrentThread,
+    /// Work-stealing runtime with the given number of worker threads.
+    MultiThread(usize),
 }
 
 pub struct SchedulerConfig {
     pub default_threads: usize,
     pub frame_threads: usize,
     pub background_threads: usize,
-    pub async_threads: usize,
+    pub async_runtime: RuntimeKind,
+    /// Slots in the bounded `VideoFrame` queue; submissions beyond it fail with `RenderQueueFull`.
+    pub frame_queue_capacity: usize,
+    /// Async jobs allowed to run at once; the rest wait for a permit, still counted by joins.
+    pub max_async_in_flight: usize,
+    /// Cores kept for frame workers alone: at least this many frame workers are spawned, and
+    /// default plus background workers are limited to the remaining cores so they cannot
+    /// starve rendering. `0` disables the reservation.
+    pub reserved_frame_threads: usize,
+    /// After this many consecutive dispatches pass over waiting normal/deferred jobs, the
+    /// oldest of them runs next, so a steady stream of higher-priority work cannot starve them.
+    pub aging_threshold: u32,
+    /// Run submitted sync jobs on the caller via `WorkerPool::run_inline` instead of on
+    /// worker threads. Only reachable through `SchedulerConfig::single_threaded`.
+    inline: bool,
 }
 
 impl SchedulerConfig {
@@ -123,14 +341,324 @@ impl SchedulerConfig {
             default_threads,
             frame_threads,
             background_threads: background,
-            async_threads,
+            async_runtime: RuntimeKind::MultiThread(async_threads),
+            frame_queue_capacity: FRAME_QUEUE_CAPACITY,
+            max_async_in_flight: MAX_ASYNC_IN_FLIGHT,
+            reserved_frame_threads: 0,
+            aging_threshold: AGING_THRESHOLD,
+            inline: false,
+        }
+    }
+
+    /// Favors frame rendering: as many frame workers as cores, fewer default workers.
+    pub fn latency_optimized(parallelism: usize) -> Self {
+        let p = parallelism.max(1);
+        Self {
+            default_threads: (p / 2).max(1),
+            frame_threads: p,
+            background_threads: 1,
+            async_runtime: RuntimeKind::MultiThread(p.clamp(1, 2)),
+            frame_queue_capacity: FRAME_QUEUE_CAPACITY,
+            max_async_in_flight: MAX_ASYNC_IN_FLIGHT,
+            reserved_frame_threads: 0,
+            aging_threshold: AGING_THRESHOLD,
+            inline: false,
+        }
+    }
+
+    /// Favors bulk work: a full set of default workers and extra background workers.
+    pub fn throughput_optimized(parallelism: usize) -> Self {
+        let p = parallelism.max(1);
+        Self {
+            default_threads: p,
+            frame_threads: (p / 4).max(1),
+            background_threads: (p / 4).max(1),
+            async_runtime: RuntimeKind::MultiThread(p.clamp(1, 4)),
+            frame_queue_capacity: FRAME_QUEUE_CAPACITY,
+            max_async_in_flight: MAX_ASYNC_IN_FLIGHT,
+            reserved_frame_threads: 0,
+            aging_threshold: AGING_THRESHOLD,
+            inline: false,
+        }
+    }
+
+    /// Start from `balanced` for this machine and override individual values.
+    pub fn builder() -> SchedulerConfigBuilder {
+        SchedulerConfigBuilder {
+            config: Self::balanced(parallelism()),
+        }
+    }
+
+    /// Thread counts with `reserved_frame_threads` applied for a machine with `cores` cores.
+    /// Default and background workers keep at least one thread each, so a reservation that
+    /// leaves fewer than two cores still oversubscribes slightly.
+    fn with_reservation(mut self, cores: usize) -> Self {
+        let reserved = self.reserved_frame_threads;
+        if reserved == 0 || self.inline {
+            return self;
+        }
+        self.frame_threads = self.frame_threads.max(reserved);
+        let shared = cores.saturating_sub(reserved).max(2);
+        if self.default_threads + self.background_threads > shared {
+            let background = self.background_threads.clamp(1, shared - 1);
+            let default = self.default_threads.min(shared - background).max(1);
+            warn!(
+                "Reserving {reserved} of {cores} cores for frame work: default workers {} -> \
+                 {default}, background workers {} -> {background}.",
+                self.default_threads, self.background_threads
+            );
+            self.default_threads = default;
+            self.background_threads = background;
+        }
+        self
+    }
+
+    /// Deterministic mode for tests: no worker threads are spawned, and queued sync jobs
+    /// only run when `WorkerPool::run_inline` (or a join) drains them on the calling thread.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn single_threaded() -> Self {
+        Self {
+            default_threads: 0,
+            frame_threads: 0,
+            background_threads: 0,
+            async_runtime: RuntimeKind::CurrentThread,
+            frame_queue_capacity: FRAME_QUEUE_CAPACITY,
+            max_async_in_flight: MAX_ASYNC_IN_FLIGHT,
+            reserved_frame_threads: 0,
+            aging_threshold: AGING_THRESHOLD,
+            inline: true,
+        }
+    }
+}
+
+/// Parallelism the presets are sized for: `LUNARIS_THREADS` if set to a positive number,
+/// otherwise `available_parallelism`, otherwise 4. Cpu quotas in containers can make
+/// `available_parallelism` misreport, which is what the override is for.
+pub fn parallelism() -> usize {
+    if let Ok(raw) = std::env::var("LUNARIS_THREADS") {
+        match raw.trim().parse::<usize>() {
+            Ok(n) if n > 0 => return n,
+            _ => warn!("Ignoring invalid LUNARIS_THREADS={raw:?}"),
+        }
+    }
+    thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or_else(|e| {
+            warn!("Could not detect available parallelism ({e}); assuming 4.");
+            4
+        })
+}
+
+/// Fluent overrides on top of a preset, e.g.
+/// `SchedulerConfig::builder().background_threads(4).frame_queue_capacity(4096).build()`.
+pub struct SchedulerConfigBuilder {
+    config: SchedulerConfig,
+}
+
+impl SchedulerConfigBuilder {
+    pub fn default_threads(mut self, threads: usize) -> Self {
+        self.config.default_threads = threads;
+        self
+    }
+    pub fn frame_threads(mut self, threads: usize) -> Self {
+        self.config.frame_threads = threads;
+        self
+    }
+    pub fn background_threads(mut self, threads: usize) -> Self {
+        self.config.background_threads = threads;
+        self
+    }
+    pub fn async_runtime(mut self, runtime: RuntimeKind) -> Self {
+        self.config.async_runtime = runtime;
+        self
+    }
+    pub fn frame_queue_capacity(mut self, capacity: usize) -> Self {
+        self.config.frame_queue_capacity = capacity;
+        self
+    }
+    pub fn max_async_in_flight(mut self, limit: usize) -> Self {
+        self.config.max_async_in_flight = limit;
+        self
+    }
+    pub fn reserved_frame_threads(mut self, threads: usize) -> Self {
+        self.config.reserved_frame_threads = threads;
+        self
+    }
+    pub fn aging_threshold(mut self, dispatches: u32) -> Self {
+        self.config.aging_threshold = dispatches;
+        self
+    }
+    pub fn build(self) -> SchedulerConfig {
+        self.config
+    }
+}
+
+impl From<SchedulerConfig> for SchedulerConfigBuilder {
+    fn from(config: SchedulerConfig) -> Self {
+        Self { config }
+    }
+}
+
+/// How long jobs on a runtime replaced by `WorkerPool::reconfigure_threads` get to finish
+/// before whatever is left is cancelled.
+pub const RETIRED_RUNTIME_GRACE: Duration = Duration::from_secs(10);
+
+/// The Tokio runtime for async jobs, plus the thread driving it if it is a current-thread one.
+struct AsyncRuntime {
+    kind: RuntimeKind,
+    // Declared before `rt` so the driver lets go of the runtime first.
+    driver: Option<Driver>,
+    rt: Arc<tokio::runtime::Runtime>,
+    /// Tasks spawned through `spawn`/`spawn_blocking` that have not finished yet.
+    in_flight: Arc<InFlight>,
+}
+
+#[derive(Default)]
+struct InFlight {
+    count: Mutex<usize>,
+    idle: Condvar,
+}
+
+/// Counts one task in `InFlight` until dropped, i.e. until the task finishes or is cancelled.
+struct InFlightGuard(Arc<InFlight>);
+
+impl InFlightGuard {
+    fn new(in_flight: &Arc<InFlight>) -> Self {
+        *in_flight.count.lock() += 1;
+        Self(in_flight.clone())
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut count = self.0.count.lock();
+        *count -= 1;
+        if *count == 0 {
+            self.0.idle.notify_all();
+        }
+    }
+}
+
+/// The thread parked in `block_on` for a current-thread runtime; stopped when dropped.
+struct Driver {
+    stop: Arc<tokio::sync::Notify>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for Driver {
+    fn drop(&mut self) {
+        self.stop.notify_one();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl AsyncRuntime {
+    fn new(kind: RuntimeKind) -> Self {
+        match kind {
+            RuntimeKind::MultiThread(threads) => Self {
+                kind,
+                rt: Arc::new(
+                    tokio::runtime::Builder::new_multi_thread()
+                        .worker_threads(threads.max(1))
+                        .enable_all()
+                        .build()
+                        .expect("failed to build tokio runtime"),
+                ),
+                driver: None,
+                in_flight: Arc::default(),
+            },
+            RuntimeKind::CurrentThread => {
+                let rt = Arc::new(
+                    tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .expect("failed to build tokio runtime"),
+                );
+                // A current-thread runtime only makes progress inside `block_on`, so park a
+                // dedicated thread there until the pool is dropped.
+                let stop = Arc::new(tokio::sync::Notify::new());
+                let thread = {
+                    let rt = rt.clone();
+                    let stop = stop.clone();
+                    thread::spawn(move || rt.block_on(stop.notified()))
+                };
+                Self {
+                    kind,
+                    driver: Some(Driver {
+                        stop,
+                        thread: Some(thread),
+                    }),
+                    rt,
+                    in_flight: Arc::default(),
+                }
+            }
+        }
+    }
+
+    fn spawn<F>(&self, future: F)
+    where
+        F: core::future::Future<Output = ()> + Send + 'static,
+    {
+        let guard = InFlightGuard::new(&self.in_flight);
+        self.rt.spawn(async move {
+            let _guard = guard;
+            future.await
+        });
+    }
+
+    fn spawn_blocking<F>(&self, task: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let guard = InFlightGuard::new(&self.in_flight);
+        self.rt.spawn_blocking(move || {
+            let _guard = guard;
+            task()
+        });
+    }
+}
+
+impl AsyncRuntime {
+    /// Wait up to `timeout` for every spawned task to finish, then shut the runtime down,
+    /// cancelling whatever is still running. Nothing new may be spawned onto it meanwhile.
+    fn drain(self, timeout: Duration) {
+        let AsyncRuntime {
+            driver,
+            rt,
+            in_flight,
+            ..
+        } = self;
+        let mut count = in_flight.count.lock();
+        let timed_out = in_flight
+            .idle
+            .wait_while_for(&mut count, |count| *count > 0, timeout)
+            .timed_out();
+        if timed_out {
+            warn!(
+                "{} async jobs did not finish within {timeout:?} of a runtime swap; cancelling them.",
+                *count
+            );
+        }
+        drop(count);
+        drop(driver);
+        match Arc::try_unwrap(rt) {
+            Ok(rt) => rt.shutdown_background(),
+            // Someone still holds it; it goes with the last reference.
+            Err(rt) => drop(rt),
         }
     }
 }
 
 pub struct WorkerPool {
     default_q: Arc<CondVarQueue<PriorityQueues>>,
+    default_depths: Arc<QueueDepths>,
     frame_q: Arc<BlockingArrayQueue<Task>>,
+    /// Serializes frame producers so a batch's free-slot check stays valid while it is pushed.
+    frame_submit: Mutex<()>,
+    /// Signalled whenever a frame task leaves the queue, for `add_job_awaitable`.
+    frame_space: Arc<Notify>,
     bg_q: Arc<CondVarQueue<VecDeque<Task>>>,
 
     // Workers
@@ -141,72 +669,141 @@ pub struct WorkerPool {
     // Counters
     fg_jobs: Arc<AtomicU64>,
     bg_jobs: Arc<AtomicU64>,
+    /// Outstanding async jobs, regardless of priority. Also counted in `fg_jobs`/`bg_jobs`.
+    async_jobs: Arc<AtomicU64>,
+    /// Caps concurrently running async jobs at `SchedulerConfig::max_async_in_flight`.
+    async_permits: Arc<Semaphore>,
+    max_async_in_flight: usize,
+    reserved_frame_threads: usize,
+    /// Outstanding jobs per priority class, indexed by `priority_slot`.
+    priority_jobs: Arc<[AtomicU64; PRIORITY_CLASSES]>,
+    /// Wait/run histograms per priority class, indexed by `priority_slot`.
+    timings: Arc<[TaskTimings; PRIORITY_CLASSES]>,
+    /// Submissions refused because their queue was full, per priority class, indexed by
+    /// `priority_slot`. Cumulative since the pool was created.
+    rejected: [AtomicU64; PRIORITY_CLASSES],
     zero_cv_lock: Arc<Mutex<()>>,
     zero_cv: Arc<Condvar>,
+    /// What each thread is running, for `inflight_tasks`.
+    inflight: Arc<Mutex<HashMap<ThreadId, RunningTask>>>,
+    /// Bumped to cancel all queued frame work at once; see `advance_render_epoch`.
+    render_epoch: Arc<AtomicU64>,
+    /// Outstanding tasks per frame number, for `barrier_frame`. Frames are removed at zero.
+    frames: Arc<Mutex<HashMap<u64, u64>>>,
+    frames_cv: Arc<Condvar>,
 
     // Control
     stopping: Arc<AtomicBool>,
+    /// Total sync worker threads across all groups, for lock-free load reporting.
+    worker_threads: AtomicUsize,
+    inline: bool,
 
-    // Async runtime
-    rt: tokio::runtime::Runtime,
+    // Async runtime, replaced by `reconfigure_threads` when the async thread count changes
+    rt: RwLock<Arc<AsyncRuntime>>,
 }
 
 impl WorkerPool {
     pub fn new(cfg: SchedulerConfig) -> Self {
+        let depths = Arc::new(QueueDepths::default());
         let pool = Self {
-            default_q: Arc::new(CondVarQueue::new(PriorityQueues::new())),
+            default_q: Arc::new(CondVarQueue::new(PriorityQueues::new(
+                depths.clone(),
+                cfg.aging_threshold,
+            ))),
+            default_depths: depths,
             frame_q: Arc::new(BlockingArrayQueue::<Task>::with_capacity(
-                FRAME_QUEUE_CAPACITY,
+                cfg.frame_queue_capacity,
             )),
+            frame_submit: Mutex::new(()),
+            frame_space: Arc::new(Notify::new()),
             bg_q: Arc::new(CondVarQueue::new(VecDeque::new())),
             default_workers: Mutex::new(Vec::new()),
             frame_workers: Mutex::new(Vec::new()),
             background_workers: Mutex::new(Vec::new()),
             fg_jobs: Arc::new(AtomicU64::new(0)),
             bg_jobs: Arc::new(AtomicU64::new(0)),
+            async_jobs: Arc::new(AtomicU64::new(0)),
+            async_permits: Arc::new(Semaphore::new(cfg.max_async_in_flight.max(1))),
+            max_async_in_flight: cfg.max_async_in_flight.max(1),
+            reserved_frame_threads: cfg.reserved_frame_threads,
+            priority_jobs: Arc::new(Default::default()),
+            timings: Arc::new(Default::default()),
+            rejected: Default::default(),
             zero_cv_lock: Arc::new(Mutex::new(())),
             zero_cv: Arc::new(Condvar::new()),
+            inflight: Arc::new(Mutex::new(HashMap::new())),
+            render_epoch: Arc::new(AtomicU64::new(0)),
+            frames: Arc::new(Mutex::new(HashMap::new())),
+            frames_cv: Arc::new(Condvar::new()),
             stopping: Arc::new(AtomicBool::new(false)),
-            rt: tokio::runtime::Builder::new_multi_thread()
-                .worker_threads(cfg.async_threads.max(1))
-                .enable_all()
-                .build()
-                .expect("failed to build tokio runtime"),
+            worker_threads: AtomicUsize::new(0),
+            inline: cfg.inline,
+            rt: RwLock::new(Arc::new(AsyncRuntime::new(cfg.async_runtime))),
         };
-        pool.spawn_workers(cfg);
+        if !pool.inline {
+            pool.spawn_workers(cfg);
+        }
         pool
     }
 
+    /// Drain every queue on the calling thread: frame tasks first, then the default queue in
+    /// priority order, then background tasks. Tasks enqueued while draining are picked up too.
+    /// Only meaningful for pools built with `SchedulerConfig::single_threaded`.
+    pub fn run_inline(&self) {
+        loop {
+            if let Some(task) = self.frame_q.q.pop() {
+                self.frame_space.notify_waiters();
+                task();
+                complete(&self.fg_jobs, &self.zero_cv_lock, &self.zero_cv);
+                continue;
+            }
+            let next = self.default_q.queue.lock().pop();
+            if let Some(task) = next {
+                task();
+                complete(&self.fg_jobs, &self.zero_cv_lock, &self.zero_cv);
+                continue;
+            }
+            let next = self.bg_q.queue.lock().pop_front();
+            if let Some(task) = next {
+                task();
+                complete(&self.bg_jobs, &self.zero_cv_lock, &self.zero_cv);
+                continue;
+            }
+            break;
+        }
+    }
+
     fn spawn_workers(&self, cfg: SchedulerConfig) {
-        // Default workers: drain PriorityQueues in priority order
+        let cfg = cfg.with_reservation(parallelism());
+        self.worker_threads.store(
+            cfg.default_threads.max(1) + cfg.frame_threads.max(1) + cfg.background_threads.max(1),
+            Ordering::Release,
+        );
+        // Default workers: drain PriorityQueues in priority order, then help with frame work
+        // before parking. Both kinds count against `fg_jobs`.
         let mut d = self.default_workers.lock();
         for _ in 0..cfg.default_threads.max(1) {
             let q = self.default_q.clone();
+            let frame_q = self.frame_q.clone();
+            let space = self.frame_space.clone();
             let stopping = self.stopping.clone();
             let fg = self.fg_jobs.clone();
             let zero_cv = self.zero_cv.clone();
             let zero_lock = self.zero_cv_lock.clone();
             d.push(thread::spawn(move || {
-                while !stopping.load(Ordering::Acquire) {
-                    let mut guard = q.queue.lock();
-                    loop {
-                        if let Some(task) = guard.pop() {
-                            drop(guard);
-                            // Execute foreground task
-                            task();
-                            // Decrement foreground counter and notify if zero
-                            if fg.fetch_sub(1, Ordering::AcqRel) == 1 {
-                                let _g = zero_lock.lock();
-                                zero_cv.notify_all();
-                                drop(_g);
-                            }
-                            break;
+                ON_WORKER.set(true);
+                let pop = |queues: &mut PriorityQueues| {
+                    queues.pop().or_else(|| {
+                        let stolen = frame_q.q.pop();
+                        if stolen.is_some() {
+                            space.notify_waiters();
                         }
-                        q.cv.wait(&mut guard);
-                        if stopping.load(Ordering::Acquire) {
-                            break;
-                        }
-                    }
+                        stolen
+                    })
+                };
+                while let Some(task) = q.next(&stopping, pop) {
+                    task();
+                    complete(&fg, &zero_lock, &zero_cv);
                 }
             }));
         }
@@ -216,40 +813,17 @@ impl WorkerPool {
         let mut f = self.frame_workers.lock();
         for _ in 0..cfg.frame_threads.max(1) {
             let q = self.frame_q.clone();
+            let space = self.frame_space.clone();
             let stopping = self.stopping.clone();
             let fg = self.fg_jobs.clone();
             let zero_cv = self.zero_cv.clone();
             let zero_lock = self.zero_cv_lock.clone();
             f.push(thread::spawn(move || {
-                while !stopping.load(Ordering::Acquire) {
-                    // Fast path: try to pop without locking
-                    if let Some(task) = q.q.pop() {
-                        task();
-                        if fg.fetch_sub(1, Ordering::AcqRel) == 1 {
-                            let _g = zero_lock.lock();
-                            zero_cv.notify_all();
-                            drop(_g);
-                        }
-                        continue;
-                    }
-                    // Slow path: wait for signal
-                    let mut guard = q.lock.lock();
-                    loop {
-                        if let Some(task) = q.q.pop() {
-                            drop(guard);
-                            task();
-                            if fg.fetch_sub(1, Ordering::AcqRel) == 1 {
-                                let _g = zero_lock.lock();
-                                zero_cv.notify_all();
-                                drop(_g);
-                            }
-                            break;
-                        }
-                        q.cv.wait(&mut guard);
-                        if stopping.load(Ordering::Acquire) {
-                            break;
-                        }
-                    }
+                ON_WORKER.set(true);
+                while let Some(task) = q.next(&stopping) {
+                    space.notify_waiters();
+                    task();
+                    complete(&fg, &zero_lock, &zero_cv);
                 }
             }));
         }
@@ -264,38 +838,99 @@ impl WorkerPool {
             let zero_cv = self.zero_cv.clone();
             let zero_lock = self.zero_cv_lock.clone();
             b.push(thread::spawn(move || {
-                while !stopping.load(Ordering::Acquire) {
-                    let mut guard = q.queue.lock();
-                    loop {
-                        if let Some(task) = guard.pop_front() {
-                            drop(guard);
-                            task();
-                            if bg.fetch_sub(1, Ordering::AcqRel) == 1 {
-                                let _g = zero_lock.lock();
-                                zero_cv.notify_all();
-                                drop(_g);
-                            }
-                            break;
-                        }
-                        q.cv.wait(&mut guard);
-                        if stopping.load(Ordering::Acquire) {
-                            break;
-                        }
-                    }
+                ON_WORKER.set(true);
+                while let Some(task) = q.next(&stopping, VecDeque::pop_front) {
+                    task();
+                    complete(&bg, &zero_lock, &zero_cv);
                 }
             }));
         }
+        drop(b);
+
+        debug_assert!(
+            self.validate_workers().is_ok(),
+            "every worker group must have at least one thread after spawning"
+        );
+    }
+
+    /// Check that no worker group has been left without threads while work is queued for it,
+    /// which would otherwise stall every join on that queue forever.
+    pub fn validate_workers(&self) -> Result {
+        if self.inline {
+            return Ok(());
+        }
+        let groups = [
+            (
+                "default",
+                self.default_workers.lock().is_empty(),
+                !self.default_q.queue.lock().is_empty(),
+            ),
+            (
+                "frame",
+                self.frame_workers.lock().is_empty(),
+                !self.frame_q.q.is_empty(),
+            ),
+            (
+                "background",
+                self.background_workers.lock().is_empty(),
+                !self.bg_q.queue.lock().is_empty(),
+            ),
+        ];
+        for (group, no_workers, has_work) in groups {
+            if no_workers && has_work {
+                error!("The {group} worker group has no threads but its queue is not empty.");
+                return Err(LunarisError::KernelInitFailed {
+                    reason: format!("no {group} workers available to drain queued jobs"),
+                });
+            }
+        }
+        Ok(())
     }
 
     pub fn add_job<T>(&self, job: Job<T>) -> Result
     where
         T: FnOnce() + Send + 'static,
     {
+        self.enqueue(job, None, None)
+    }
+
+    /// `add_job` with a label that `inflight_tasks` reports while the job runs.
+    pub fn add_job_labeled<T>(&self, job: Job<T>, label: &'static str) -> Result
+    where
+        T: FnOnce() + Send + 'static,
+    {
+        self.enqueue(job, None, Some(label))
+    }
+
+    /// Like `add_job`, but a `Normal` or `Deferred` job still queued at `deadline` is promoted
+    /// to the back of the `Immediate` queue. Other priorities ignore the deadline.
+    pub fn add_job_with_deadline<T>(&self, job: Job<T>, deadline: Instant) -> Result
+    where
+        T: FnOnce() + Send + 'static,
+    {
+        self.enqueue(job, Some(deadline), None)
+    }
+
+    fn enqueue<T>(
+        &self,
+        job: Job<T>,
+        deadline: Option<Instant>,
+        label: Option<&'static str>,
+    ) -> Result
+    where
+        T: FnOnce() + Send + 'static,
+    {
+        let slot = priority_slot(&job.priority);
+        let task = if matches!(job.priority, Priority::VideoFrame) {
+            self.track_priority(slot, label, self.guard_epoch(job.inner))
+        } else {
+            self.track_priority(slot, label, job.inner)
+        };
         match job.priority {
             Priority::Background => {
                 self.bg_jobs.fetch_add(1, Ordering::Release);
                 let mut guard = self.bg_q.queue.lock();
-                guard.push_back(Box::new(job.inner));
+                guard.push_back(task);
                 drop(guard);
                 self.bg_q.cv.notify_one();
                 Ok(())
@@ -303,20 +938,26 @@ impl WorkerPool {
             Priority::VideoFrame => {
                 self.fg_jobs.fetch_add(1, Ordering::Release);
                 // Try to enqueue into the bounded frame queue
-                match self.frame_q.q.push(Box::new(job.inner)) {
+                let _submit = self.frame_submit.lock();
+                match self.frame_q.q.push(task) {
                     Ok(()) => {
-                        // Wake one waiter
-                        self.frame_q.cv.notify_one();
+                        self.signal_frame_work(false);
                         Ok(())
                     }
-                    Err(_task) => Err(LunarisError::RenderQueueFull),
+                    Err(_task) => {
+                        // The task never runs; roll back its counters so joins don't hang.
+                        complete(&self.priority_jobs[slot], &self.zero_cv_lock, &self.zero_cv);
+                        complete(&self.fg_jobs, &self.zero_cv_lock, &self.zero_cv);
+                        self.rejected[slot].fetch_add(1, Ordering::Relaxed);
+                        Err(LunarisError::RenderQueueFull)
+                    }
                 }
             }
             // Immediate/Normal/Deferred
             p => {
                 self.fg_jobs.fetch_add(1, Ordering::Release);
                 let mut guard = self.default_q.queue.lock();
-                guard.push(p, Box::new(job.inner));
+                guard.push(p, task, deadline);
                 drop(guard);
                 self.default_q.cv.notify_one();
                 Ok(())
@@ -324,45 +965,292 @@ impl WorkerPool {
         }
     }
 
+    /// Like `add_job`, but instead of failing with `RenderQueueFull` the returned future waits
+    /// until the frame queue has room, without parking a thread. Resolves once the job is
+    /// enqueued. Nothing is counted until the job is actually pushed, so dropping the future
+    /// early leaves the pool untouched. Only `VideoFrame` jobs can wait; others are enqueued
+    /// immediately.
+    pub async fn add_job_awaitable<T>(&self, job: Job<T>) -> Result
+    where
+        T: FnOnce() + Send + 'static,
+    {
+        if !matches!(job.priority, Priority::VideoFrame) {
+            return self.add_job(job);
+        }
+        let slot = priority_slot(&Priority::VideoFrame);
+        let inner = job.inner;
+        loop {
+            // Registered before checking, so a slot freed in between still wakes us.
+            let space = self.frame_space.notified();
+            tokio::pin!(space);
+            space.as_mut().enable();
+            {
+                let _submit = self.frame_submit.lock();
+                if self.frame_queue_remaining() > 0 {
+                    self.fg_jobs.fetch_add(1, Ordering::Release);
+                    let task = self.track_priority(slot, None, self.guard_epoch(inner));
+                    if self.frame_q.q.push(task).is_err() {
+                        unreachable!("frame queue slots are reserved under frame_submit");
+                    }
+                    drop(_submit);
+                    self.signal_frame_work(false);
+                    return Ok(());
+                }
+            }
+            space.await;
+        }
+    }
+
+    /// Enqueue every task of one frame as `VideoFrame` work, or none of them. If the frame queue
+    /// cannot hold the whole batch it is returned untouched, so a frame is never half-submitted.
+    /// The tasks count towards `barrier_frame(frame)`.
+    pub fn add_frame_batch(
+        &self,
+        frame: u64,
+        tasks: Vec<Task>,
+    ) -> std::result::Result<(), Vec<Task>> {
+        // Producers are serialized here, so free slots can only grow until we are done pushing.
+        let _submit = self.frame_submit.lock();
+        if self.frame_queue_remaining() < tasks.len() {
+            return Err(tasks);
+        }
+        let slot = priority_slot(&Priority::VideoFrame);
+        self.fg_jobs
+            .fetch_add(tasks.len() as u64, Ordering::Release);
+        *self.frames.lock().entry(frame).or_default() += tasks.len() as u64;
+        for task in tasks {
+            let task = self.guard_epoch(task);
+            let task = self.track_priority(slot, None, self.track_frame(frame, task));
+            if self.frame_q.q.push(task).is_err() {
+                unreachable!("frame queue slots are reserved under frame_submit");
+            }
+        }
+        self.signal_frame_work(true);
+        Ok(())
+    }
+
+    /// Wake frame workers after a push (one, or all for a batch). Once the frame queue is at
+    /// least half full, an idle default worker is woken too so it can steal from it.
+    fn signal_frame_work(&self, all: bool) {
+        if all {
+            self.frame_q.notify_all();
+        } else {
+            self.frame_q.notify_one();
+        }
+        if self.frame_q.q.len() * 2 >= self.frame_q.q.capacity() {
+            self.default_q.wake_one();
+        }
+    }
+
+    /// Wrap a frame task so its frame's outstanding count drops once it has run (or been
+    /// skipped, or panicked), waking `barrier_frame` when the frame is complete.
+    fn track_frame<T>(&self, frame: u64, task: T) -> impl FnOnce() + Send + 'static
+    where
+        T: FnOnce() + Send + 'static,
+    {
+        let done = FrameTask {
+            frame,
+            frames: self.frames.clone(),
+            frames_cv: self.frames_cv.clone(),
+        };
+        move || {
+            let _done = done;
+            task();
+        }
+    }
+
+    /// Block until every task submitted for `frame` with `add_frame_batch` has finished.
+    /// Returns immediately if none are outstanding. Unlike `join_sync`, other work is ignored.
+    pub fn barrier_frame(&self, frame: u64) -> Result {
+        reject_reentrant_join()?;
+        if self.inline {
+            self.run_inline();
+        }
+        self.validate_workers()?;
+        let mut outstanding = self.frames.lock();
+        while outstanding.contains_key(&frame) {
+            self.frames_cv.wait(&mut outstanding);
+        }
+        Ok(())
+    }
+
+    /// Free slots left in the frame queue. Lock-free, so producers can throttle before
+    /// running into `RenderQueueFull`. Only a snapshot: other producers may take slots next.
+    pub fn frame_queue_remaining(&self) -> usize {
+        self.frame_q
+            .q
+            .capacity()
+            .saturating_sub(self.frame_q.q.len())
+    }
+
+    /// Wrap a frame task so it is skipped if the render epoch advances before it runs.
+    fn guard_epoch<T>(&self, task: T) -> impl FnOnce() + Send + 'static
+    where
+        T: FnOnce() + Send + 'static,
+    {
+        let submitted = self.render_epoch.load(Ordering::Acquire);
+        let epoch = self.render_epoch.clone();
+        move || {
+            if epoch.load(Ordering::Acquire) == submitted {
+                task();
+            }
+        }
+    }
+
+    /// Invalidate every frame job submitted so far. Queued ones are skipped when picked up
+    /// (still counted as completed); ones already running finish normally.
+    /// Returns the new epoch.
+    pub fn advance_render_epoch(&self) -> u64 {
+        self.render_epoch.fetch_add(1, Ordering::AcqRel) + 1
+    }
+
+    pub fn render_epoch(&self) -> u64 {
+        self.render_epoch.load(Ordering::Acquire)
+    }
+
+    /// Count `task` against its priority class and wrap it so the count drops once it has run.
+    /// The wrapper also records the task's wait and run time for `profile_detailed`, and
+    /// occupies its thread's `inflight_tasks` slot while running.
+    fn track_priority<T>(&self, slot: usize, label: Option<&'static str>, task: T) -> Task
+    where
+        T: FnOnce() + Send + 'static,
+    {
+        self.priority_jobs[slot].fetch_add(1, Ordering::Release);
+        let counters = self.priority_jobs.clone();
+        let timings = self.timings.clone();
+        let inflight = self.inflight.clone();
+        let zero_cv = self.zero_cv.clone();
+        let zero_lock = self.zero_cv_lock.clone();
+        let enqueued = Instant::now();
+        Box::new(move || {
+            let started = Instant::now();
+            timings[slot].wait.record(started - enqueued);
+            {
+                let _running = Running::start(&inflight, slot, label, started);
+                task();
+            }
+            timings[slot].run.record(started.elapsed());
+            complete(&counters[slot], &zero_lock, &zero_cv);
+        })
+    }
+
+    /// Sync tasks running right now, longest-running first. Meant for diagnosing a join that
+    /// does not return; async jobs are not included.
+    pub fn inflight_tasks(&self) -> Vec<TaskInfo> {
+        let now = Instant::now();
+        let mut tasks: Vec<TaskInfo> = self
+            .inflight
+            .lock()
+            .iter()
+            .map(|(thread, task)| TaskInfo {
+                label: task.label,
+                priority: slot_priority(task.slot),
+                running_for: now.saturating_duration_since(task.started),
+                thread: *thread,
+            })
+            .collect();
+        tasks.sort_by(|a, b| b.running_for.cmp(&a.running_for));
+        tasks
+    }
+
+    /// Like `add_job`, but `Placement::Io` runs the job on Tokio's blocking pool.
+    pub fn add_job_placed<T>(&self, job: Job<T>, placement: Placement) -> Result
+    where
+        T: FnOnce() + Send + 'static,
+    {
+        if !matches!(placement, Placement::Io) {
+            return self.add_job(job);
+        }
+        let jobs = if matches!(job.priority, Priority::Background) {
+            self.bg_jobs.clone()
+        } else {
+            self.fg_jobs.clone()
+        };
+        jobs.fetch_add(1, Ordering::Release);
+        let task = self.track_priority(priority_slot(&job.priority), None, job.inner);
+        let zero_cv = self.zero_cv.clone();
+        let zero_lock = self.zero_cv_lock.clone();
+        self.rt.read().spawn_blocking(move || {
+            task();
+            complete(&jobs, &zero_lock, &zero_cv);
+        });
+        Ok(())
+    }
+
     pub fn add_job_async<F, Fut>(&self, job: AsyncJob<F, Fut>) -> Result
     where
         F: FnOnce() -> Fut + Send + 'static,
         Fut: core::future::Future<Output = ()> + Send + 'static,
     {
+        self.add_job_async_placed(job, Placement::Auto)
+    }
+
+    /// Like `add_job_async`, but `Placement::Cpu` drives the future on Tokio's blocking pool.
+    /// The job still holds an in-flight permit while it runs there.
+    pub fn add_job_async_placed<F, Fut>(
+        &self,
+        job: AsyncJob<F, Fut>,
+        placement: Placement,
+    ) -> Result
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: core::future::Future<Output = ()> + Send + 'static,
+    {
+        let cpu_bound = matches!(placement, Placement::Cpu);
         let is_bg = matches!(job.priority, Priority::Background);
         if is_bg {
             self.bg_jobs.fetch_add(1, Ordering::Release);
         } else {
             self.fg_jobs.fetch_add(1, Ordering::Release);
         }
+        self.async_jobs.fetch_add(1, Ordering::Release);
+        let slot = priority_slot(&job.priority);
+        self.priority_jobs[slot].fetch_add(1, Ordering::Release);
 
-        let priority = job.priority;
-        let fg = self.fg_jobs.clone();
-        let bg = self.bg_jobs.clone();
-        let zero_cv = self.zero_cv.clone();
-        let zero_lock = self.zero_cv_lock.clone();
+        let permits = self.async_permits.clone();
+        let timings = self.timings.clone();
+        let enqueued = Instant::now();
+
+        // Released when the task ends, including when it is cancelled with a retired runtime.
+        let done = AsyncCompletion {
+            slot,
+            counters: self.priority_jobs.clone(),
+            async_jobs: self.async_jobs.clone(),
+            class_jobs: if is_bg {
+                self.bg_jobs.clone()
+            } else {
+                self.fg_jobs.clone()
+            },
+            zero_lock: self.zero_cv_lock.clone(),
+            zero_cv: self.zero_cv.clone(),
+        };
 
         // Spawn on runtime; we could bias priority by spawning onto local sets
-        self.rt.spawn(async move {
-            (job.inner)().await;
-            // decrement and notify
-            if matches!(priority, Priority::Background) {
-                if bg.fetch_sub(1, Ordering::AcqRel) == 1 {
-                    let _g = zero_lock.lock();
-                    zero_cv.notify_all();
-                    drop(_g);
-                }
-            } else if fg.fetch_sub(1, Ordering::AcqRel) == 1 {
-                let _g = zero_lock.lock();
-                zero_cv.notify_all();
-                drop(_g);
+        self.rt.read().spawn(async move {
+            let _done = done;
+            // Never closed, so this only waits for a slot.
+            let _permit = permits.acquire_owned().await;
+            let started = Instant::now();
+            timings[slot].wait.record(started - enqueued);
+            let future = (job.inner)();
+            if cpu_bound {
+                let handle = tokio::runtime::Handle::current();
+                let _ = tokio::task::spawn_blocking(move || handle.block_on(future)).await;
+            } else {
+                future.await;
             }
+            timings[slot].run.record(started.elapsed());
         });
 
         Ok(())
     }
 
     pub fn join_sync(&self) -> Result {
+        reject_reentrant_join()?;
+        if self.inline {
+            self.run_inline();
+        }
+        self.validate_workers()?;
         let mut g = self.zero_cv_lock.lock();
         while self.fg_jobs.load(Ordering::Acquire) != 0 {
             self.zero_cv.wait(&mut g);
@@ -370,7 +1258,55 @@ impl WorkerPool {
         Ok(())
     }
 
+    /// Wait until every job at `min` priority or more urgent has finished, ignoring the rest.
+    ///
+    /// Urgency runs `VideoFrame` > `Immediate` > `Normal` > `Deferred` > `Background`, so
+    /// `join_priority(Priority::Immediate)` waits for frame and immediate work only. Each class
+    /// is counted from submission until its closure (or future) returns, sync and async alike.
+    pub fn join_priority(&self, min: Priority) -> Result {
+        reject_reentrant_join()?;
+        if self.inline {
+            self.run_inline();
+        }
+        let classes = &self.priority_jobs[..=priority_slot(&min)];
+        let mut g = self.zero_cv_lock.lock();
+        while classes.iter().any(|c| c.load(Ordering::Acquire) != 0) {
+            self.zero_cv.wait(&mut g);
+        }
+        Ok(())
+    }
+
+    /// Wait for every async job (any priority) to finish, independent of sync work.
+    pub fn join_async(&self) -> Result {
+        let mut g = self.zero_cv_lock.lock();
+        while self.async_jobs.load(Ordering::Acquire) != 0 {
+            self.zero_cv.wait(&mut g);
+        }
+        Ok(())
+    }
+
+    /// Like `join_async`, but gives up with `Timeout` once `timeout` has elapsed.
+    pub fn join_async_timeout(&self, timeout: Duration) -> Result {
+        let deadline = Instant::now() + timeout;
+        let mut g = self.zero_cv_lock.lock();
+        while self.async_jobs.load(Ordering::Acquire) != 0 {
+            if self.zero_cv.wait_until(&mut g, deadline).timed_out()
+                && self.async_jobs.load(Ordering::Acquire) != 0
+            {
+                return Err(LunarisError::Timeout {
+                    duration_ms: timeout.as_millis() as u64,
+                });
+            }
+        }
+        Ok(())
+    }
+
     pub fn join_all(&self) -> Result {
+        reject_reentrant_join()?;
+        if self.inline {
+            self.run_inline();
+        }
+        self.validate_workers()?;
         let mut g = self.zero_cv_lock.lock();
         while self.fg_jobs.load(Ordering::Acquire) != 0 || self.bg_jobs.load(Ordering::Acquire) != 0
         {
@@ -379,9 +1315,27 @@ impl WorkerPool {
         Ok(())
     }
 
-    pub fn reconfigure_threads(&self, default: usize, frame: usize, background: usize) {
-        // Stop all workers and respawn with new counts
+    /// Set `stopping` and wake every parked worker so it sees the flag; otherwise joining the
+    /// workers never returns.
+    pub(crate) fn stop_workers(&self) {
         self.stopping.store(true, Ordering::Release);
+        self.default_q.wake_all();
+        self.frame_q.notify_all();
+        self.bg_q.wake_all();
+    }
+
+    pub fn reconfigure_threads(
+        &self,
+        default: usize,
+        frame: usize,
+        background: usize,
+        async_threads: usize,
+    ) {
+        if self.inline {
+            return;
+        }
+        // Stop all workers and respawn with new counts.
+        self.stop_workers();
         {
             let mut v = self.default_workers.lock();
             for h in v.drain(..) {
@@ -401,34 +1355,238 @@ impl WorkerPool {
             }
         }
         self.stopping.store(false, Ordering::Release);
+        // Only the thread count of a multi-threaded runtime is adjustable.
+        let async_runtime = match self.rt.read().kind {
+            RuntimeKind::CurrentThread => RuntimeKind::CurrentThread,
+            RuntimeKind::MultiThread(_) => RuntimeKind::MultiThread(async_threads.max(1)),
+        };
         self.spawn_workers(SchedulerConfig {
             default_threads: default.max(1),
             frame_threads: frame.max(1),
             background_threads: background.max(1),
-            async_threads: 1, // unchanged; reconfiguring async would need rebuilding the runtime
+            async_runtime: self.rebuild_runtime(async_runtime),
+            frame_queue_capacity: self.frame_q.q.capacity(), // fixed at construction
+            max_async_in_flight: self.max_async_in_flight,
+            reserved_frame_threads: self.reserved_frame_threads,
+            aging_threshold: self.default_q.queue.lock().aging_threshold, // fixed at construction
+            inline: false,
+        });
+    }
+    /// Switch async jobs to a fresh runtime of `kind`, unless the current one already is.
+    /// A helper thread waits up to `RETIRED_RUNTIME_GRACE` for jobs still on the old runtime
+    /// to finish there before shutting it down; jobs it has to cancel still release their
+    /// counters.
+    fn rebuild_runtime(&self, kind: RuntimeKind) -> RuntimeKind {
+        if self.rt.read().kind == kind {
+            return kind;
+        }
+        let old = std::mem::replace(&mut *self.rt.write(), Arc::new(AsyncRuntime::new(kind)));
+        thread::spawn(move || match Arc::try_unwrap(old) {
+            Ok(old) => old.drain(RETIRED_RUNTIME_GRACE),
+            Err(old) => drop(old),
         });
+        kind
     }
+
+    /// Worker threads of the async runtime.
+    pub fn async_threads(&self) -> usize {
+        match self.rt.read().kind {
+            RuntimeKind::CurrentThread => 1,
+            RuntimeKind::MultiThread(threads) => threads,
+        }
+    }
+
+    /// Outstanding (queued or running) sync and async jobs per sync worker thread.
+    /// `0.0` means idle, `1.0` means every worker has work, and above that jobs are waiting.
+    /// Reads only atomics, so it is cheap enough to poll every frame.
+    pub fn load_factor(&self) -> f32 {
+        let jobs = self.fg_jobs.load(Ordering::Relaxed) + self.bg_jobs.load(Ordering::Relaxed);
+        let workers = self.worker_threads.load(Ordering::Relaxed).max(1);
+        jobs as f32 / workers as f32
+    }
+    /// Number of slots in the frame queue, for reporting `profile().frame` as a fill ratio.
+    pub fn frame_queue_capacity(&self) -> usize {
+        self.frame_q.q.capacity()
+    }
+    /// Snapshot of queue depths and worker counts. Only reads atomics, so polling it from the
+    /// UI every frame never contends with the workers on the queue locks.
     pub fn profile(&self) -> OrchestratorProfile {
-        let q = self.default_q.queue.lock();
         OrchestratorProfile {
-            immediate: q.immediate.len() as u64,
-            normal: q.normal.len() as u64,
-            deferred: q.deferred.len() as u64,
+            immediate: self.default_depths.immediate.load(Ordering::Relaxed),
+            normal: self.default_depths.normal.load(Ordering::Relaxed),
+            deferred: self.default_depths.deferred.load(Ordering::Relaxed),
             frame: self.frame_q.q.len() as u64,
-            running_tasks: (self.frame_workers.lock().len()
-                + self.default_workers.lock().len()
-                + self.background_workers.lock().len()) as u64,
+            running_tasks: self.worker_threads.load(Ordering::Relaxed) as u64,
+        }
+    }
+    /// `profile()` plus wait/run latency histograms per priority class, cumulative since the
+    /// pool was created.
+    pub fn profile_detailed(&self) -> DetailedProfile {
+        DetailedProfile {
+            profile: self.profile(),
+            timings: std::array::from_fn(|slot| self.timings[slot].snapshot()),
+            rejected: std::array::from_fn(|slot| self.rejected[slot].load(Ordering::Relaxed)),
+            reserved_frame_threads: self.reserved_frame_threads,
+        }
+    }
+}
+
+thread_local! {
+    /// Set on pool worker threads. A worker that joins the pool may be waiting on jobs that
+    /// only it (or its equally blocked siblings) could run.
+    static ON_WORKER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Fail fast with `DeadlockDetected` instead of hanging when a job tries to join its own pool.
+fn reject_reentrant_join() -> Result {
+    if ON_WORKER.get() {
+        error!("join called from inside an orchestrator worker; refusing to deadlock.");
+        return Err(LunarisError::DeadlockDetected {
+            component: "orchestrator".into(),
+        });
+    }
+    Ok(())
+}
+
+const PRIORITY_CLASSES: usize = 5;
+
+pub struct DetailedProfile {
+    pub profile: OrchestratorProfile,
+    /// Indexed most urgent first: `VideoFrame`, `Immediate`, `Normal`, `Deferred`, `Background`.
+    pub timings: [TimingSnapshot; PRIORITY_CLASSES],
+    /// Jobs refused because their queue was full since the pool was created, same indexing.
+    /// Only the bounded `VideoFrame` queue rejects jobs today.
+    pub rejected: [u64; PRIORITY_CLASSES],
+    /// `SchedulerConfig::reserved_frame_threads` of the pool.
+    pub reserved_frame_threads: usize,
+}
+
+impl DetailedProfile {
+    pub fn timings_for(&self, priority: Priority) -> &TimingSnapshot {
+        &self.timings[priority_slot(&priority)]
+    }
+    pub fn rejected_for(&self, priority: Priority) -> u64 {
+        self.rejected[priority_slot(&priority)]
+    }
+}
+
+/// A sync task that is running now; see `WorkerPool::inflight_tasks`.
+pub struct TaskInfo {
+    /// Set with `add_job_labeled`.
+    pub label: Option<&'static str>,
+    pub priority: Priority,
+    pub running_for: Duration,
+    /// The worker (or blocking-pool) thread running it.
+    pub thread: ThreadId,
+}
+
+struct RunningTask {
+    label: Option<&'static str>,
+    slot: usize,
+    started: Instant,
+}
+
+/// Occupies the current thread's `inflight` slot until dropped, even if the task panics.
+struct Running<'a> {
+    inflight: &'a Mutex<HashMap<ThreadId, RunningTask>>,
+}
+
+impl<'a> Running<'a> {
+    fn start(
+        inflight: &'a Mutex<HashMap<ThreadId, RunningTask>>,
+        slot: usize,
+        label: Option<&'static str>,
+        started: Instant,
+    ) -> Self {
+        let task = RunningTask {
+            label,
+            slot,
+            started,
+        };
+        inflight.lock().insert(thread::current().id(), task);
+        Self { inflight }
+    }
+}
+
+impl Drop for Running<'_> {
+    fn drop(&mut self) {
+        self.inflight.lock().remove(&thread::current().id());
+    }
+}
+
+/// Counts one task towards its frame in `WorkerPool::frames` until dropped, even if the task
+/// panics.
+struct FrameTask {
+    frame: u64,
+    frames: Arc<Mutex<HashMap<u64, u64>>>,
+    frames_cv: Arc<Condvar>,
+}
+
+impl Drop for FrameTask {
+    fn drop(&mut self) {
+        let mut outstanding = self.frames.lock();
+        if let Some(count) = outstanding.get_mut(&self.frame) {
+            *count -= 1;
+            if *count == 0 {
+                outstanding.remove(&self.frame);
+                self.frames_cv.notify_all();
+            }
         }
     }
 }
 
+/// Inverse of `priority_slot`.
+fn slot_priority(slot: usize) -> Priority {
+    match slot {
+        0 => Priority::VideoFrame,
+        1 => Priority::Immediate,
+        2 => Priority::Normal,
+        3 => Priority::Deferred,
+        _ => Priority::Background,
+    }
+}
+
+/// Index of a priority class, most urgent first.
+fn priority_slot(priority: &Priority) -> usize {
+    match priority {
+        Priority::VideoFrame => 0,
+        Priority::Immediate => 1,
+        Priority::Normal => 2,
+        Priority::Deferred => 3,
+        Priority::Background => 4,
+    }
+}
+
+/// Counters an async job holds until it finishes or is cancelled.
+struct AsyncCompletion {
+    slot: usize,
+    counters: Arc<[AtomicU64; PRIORITY_CLASSES]>,
+    async_jobs: Arc<AtomicU64>,
+    /// `fg_jobs` or `bg_jobs`, by the job's priority.
+    class_jobs: Arc<AtomicU64>,
+    zero_lock: Arc<Mutex<()>>,
+    zero_cv: Arc<Condvar>,
+}
+
+impl Drop for AsyncCompletion {
+    fn drop(&mut self) {
+        complete(&self.counters[self.slot], &self.zero_lock, &self.zero_cv);
+        complete(&self.async_jobs, &self.zero_lock, &self.zero_cv);
+        complete(&self.class_jobs, &self.zero_lock, &self.zero_cv);
+    }
+}
+
+/// Decrement a job counter and wake joiners once it reaches zero.
+fn complete(jobs: &AtomicU64, zero_lock: &Mutex<()>, zero_cv: &Condvar) {
+    if jobs.fetch_sub(1, Ordering::AcqRel) == 1 {
+        let _g = zero_lock.lock();
+        zero_cv.notify_all();
+    }
+}
+
 impl Drop for WorkerPool {
     fn drop(&mut self) {
-        self.stopping.store(true, Ordering::Release);
-        // Wake all workers so they can exit
-        self.default_q.cv.notify_all();
-        self.frame_q.cv.notify_all();
-        self.bg_q.cv.notify_all();
+        self.stop_workers();
         for h in self.default_workers.get_mut().drain(..) {
             let _ = h.join();
         }
@@ -445,8 +1603,8 @@ impl Drop for WorkerPool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::time::Duration;
 
     #[test]
@@ -457,15 +1615,417 @@ mod tests {
 
         for _ in 0..job_count {
             let c = counter.clone();
-            pool.add_job(Job::new(move || {
-                std::thread::sleep(Duration::from_millis(10));
-                c.fetch_add(1, Ordering::Relaxed);
-            })
-            .with_priority(Priority::Normal))
+            pool.add_job(
+                Job::new(move || {
+                    std::thread::sleep(Duration::from_millis(10));
+                    c.fetch_add(1, Ordering::Relaxed);
+                })
+                .with_priority(Priority::Normal),
+            )
             .unwrap();
         }
 
         pool.join_sync().unwrap();
         assert_eq!(counter.load(Ordering::Relaxed), job_count);
     }
+
+    #[test]
+    fn test_inline_pool_runs_in_priority_order() {
+        let pool = WorkerPool::new(SchedulerConfig::single_threaded());
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        for (label, priority) in [
+            ("background", Priority::Background),
+            ("deferred", Priority::Deferred),
+            ("normal", Priority::Normal),
+            ("frame", Priority::VideoFrame),
+            ("immediate", Priority::Immediate),
+        ] {
+            let o = order.clone();
+            pool.add_job(Job::new(move || o.lock().push(label)).with_priority(priority))
+                .unwrap();
+        }
+        assert!(order.lock().is_empty());
+
+        pool.run_inline();
+        assert_eq!(
+            *order.lock(),
+            ["frame", "immediate", "normal", "deferred", "background"]
+        );
+        pool.join_all().unwrap();
+    }
+
+    #[test]
+    fn test_due_deadline_job_is_promoted() {
+        let pool = WorkerPool::new(SchedulerConfig::single_threaded());
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let o = order.clone();
+        pool.add_job(Job::new(move || o.lock().push("normal")).with_priority(Priority::Normal))
+            .unwrap();
+        let o = order.clone();
+        pool.add_job_with_deadline(
+            Job::new(move || o.lock().push("due")).with_priority(Priority::Deferred),
+            Instant::now(),
+        )
+        .unwrap();
+        let o = order.clone();
+        pool.add_job_with_deadline(
+            Job::new(move || o.lock().push("later")).with_priority(Priority::Deferred),
+            Instant::now() + Duration::from_secs(3600),
+        )
+        .unwrap();
+
+        pool.run_inline();
+        assert_eq!(*order.lock(), ["due", "normal", "later"]);
+    }
+
+    #[test]
+    fn test_aging_runs_deferred_task_under_steady_immediate_load() {
+        let mut queues = PriorityQueues::new(Arc::new(QueueDepths::default()), 4);
+        let ran = Arc::new(AtomicBool::new(false));
+        let r = ran.clone();
+        queues.push(
+            Priority::Deferred,
+            Box::new(move || r.store(true, Ordering::Relaxed)),
+            None,
+        );
+        for _ in 0..2 {
+            queues.push(Priority::Immediate, Box::new(|| {}), None);
+        }
+        // Keep the immediate queue non-empty: one new immediate task per dispatch.
+        let mut dispatches = 0;
+        while !ran.load(Ordering::Relaxed) {
+            assert!(dispatches <= 5, "deferred task starved");
+            queues.push(Priority::Immediate, Box::new(|| {}), None);
+            queues.pop().unwrap()();
+            dispatches += 1;
+        }
+        assert_eq!(dispatches, 5);
+        // The counter reset, so immediate work goes first again.
+        assert_eq!(queues.bypassed, 0);
+    }
+
+    #[test]
+    fn test_barrier_frame_waits_for_its_tiles_only() {
+        let pool = WorkerPool::new(SchedulerConfig::balanced(4));
+        let done = Arc::new(AtomicUsize::new(0));
+        let tiles: Vec<Task> = (0..8)
+            .map(|_| {
+                let d = done.clone();
+                Box::new(move || {
+                    std::thread::sleep(Duration::from_millis(5));
+                    d.fetch_add(1, Ordering::Relaxed);
+                }) as Task
+            })
+            .collect();
+        assert!(pool.add_frame_batch(7, tiles).is_ok());
+
+        pool.barrier_frame(7).unwrap();
+        assert_eq!(done.load(Ordering::Relaxed), 8);
+        // Nothing was ever submitted for this frame.
+        pool.barrier_frame(8).unwrap();
+    }
+
+    #[test]
+    fn test_panicking_frame_task_still_releases_barrier_frame() {
+        let pool = WorkerPool::new(SchedulerConfig::balanced(4));
+        *pool.frames.lock().entry(3).or_default() += 1;
+        let task = pool.track_frame(3, || panic!("tile failed"));
+        assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(task)).is_err());
+
+        pool.barrier_frame(3).unwrap();
+    }
+
+    #[test]
+    fn test_join_priority_ignores_lower_priority_work() {
+        let pool = WorkerPool::new(SchedulerConfig::balanced(4));
+        let (release, blocked) = std::sync::mpsc::channel::<()>();
+        let deferred_done = Arc::new(AtomicUsize::new(0));
+        let immediate_done = Arc::new(AtomicUsize::new(0));
+
+        let d = deferred_done.clone();
+        pool.add_job(
+            Job::new(move || {
+                blocked.recv().unwrap();
+                d.fetch_add(1, Ordering::Relaxed);
+            })
+            .with_priority(Priority::Deferred),
+        )
+        .unwrap();
+        let i = immediate_done.clone();
+        pool.add_job(
+            Job::new(move || {
+                i.fetch_add(1, Ordering::Relaxed);
+            })
+            .with_priority(Priority::Immediate),
+        )
+        .unwrap();
+
+        pool.join_priority(Priority::Immediate).unwrap();
+        assert_eq!(immediate_done.load(Ordering::Relaxed), 1);
+        assert_eq!(deferred_done.load(Ordering::Relaxed), 0);
+
+        release.send(()).unwrap();
+        pool.join_sync().unwrap();
+        assert_eq!(deferred_done.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_join_from_worker_is_rejected() {
+        let pool = Arc::new(WorkerPool::new(SchedulerConfig::balanced(2)));
+        let (tx, rx) = std::sync::mpsc::channel();
+        let inner = pool.clone();
+        pool.add_job(Job::new(move || {
+            tx.send(inner.join_sync().is_err()).unwrap()
+        }))
+        .unwrap();
+        assert!(rx.recv().unwrap());
+        pool.join_sync().unwrap();
+    }
+
+    #[test]
+    fn test_reconfigure_rebuilds_async_runtime_without_losing_jobs() {
+        let pool = Arc::new(WorkerPool::new(
+            SchedulerConfig::builder()
+                .async_runtime(RuntimeKind::MultiThread(1))
+                .build(),
+        ));
+        let done = Arc::new(AtomicUsize::new(0));
+        let submit = |pool: &WorkerPool| {
+            for _ in 0..8 {
+                let d = done.clone();
+                pool.add_job_async(AsyncJob::new(move || async move {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    d.fetch_add(1, Ordering::Relaxed);
+                }))
+                .unwrap();
+            }
+        };
+        submit(&pool);
+        // The first batch is still sleeping on the old runtime when it is replaced.
+        pool.reconfigure_threads(2, 1, 1, 3);
+        assert_eq!(pool.async_threads(), 3);
+        submit(&pool);
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let joiner = pool.clone();
+        std::thread::spawn(move || tx.send(joiner.join_all()).unwrap());
+        rx.recv_timeout(Duration::from_secs(30))
+            .expect("join_all stalled across the runtime swap")
+            .unwrap();
+        assert_eq!(done.load(Ordering::Relaxed), 16);
+    }
+
+    #[test]
+    fn test_reconfigure_keeps_a_current_thread_runtime() {
+        let pool = WorkerPool::new(
+            SchedulerConfig::builder()
+                .async_runtime(RuntimeKind::CurrentThread)
+                .build(),
+        );
+        pool.reconfigure_threads(2, 1, 1, 4);
+        assert_eq!(pool.rt.read().kind, RuntimeKind::CurrentThread);
+        assert_eq!(pool.async_threads(), 1);
+    }
+
+    #[test]
+    fn test_idle_default_workers_drain_a_flooded_frame_queue() {
+        const CAPACITY: usize = 4;
+        let pool = WorkerPool::new(
+            SchedulerConfig::builder()
+                .default_threads(2)
+                .frame_threads(1)
+                .frame_queue_capacity(CAPACITY)
+                .build(),
+        );
+        let (release, gate) = std::sync::mpsc::channel::<()>();
+        let (started_tx, started) = std::sync::mpsc::channel();
+        // Keep the only frame worker busy for the whole flood.
+        pool.add_job(
+            Job::new(move || {
+                started_tx.send(()).unwrap();
+                gate.recv().unwrap();
+            })
+            .with_priority(Priority::VideoFrame),
+        )
+        .unwrap();
+        started.recv().unwrap();
+
+        let ran = Arc::new(AtomicUsize::new(0));
+        let total = CAPACITY * 8;
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        for _ in 0..total {
+            loop {
+                let r = ran.clone();
+                let job = Job::new(move || {
+                    r.fetch_add(1, Ordering::Relaxed);
+                })
+                .with_priority(Priority::VideoFrame);
+                match pool.add_job(job) {
+                    Ok(()) => break,
+                    Err(LunarisError::RenderQueueFull) => {
+                        assert!(
+                            std::time::Instant::now() < deadline,
+                            "frame queue never drained"
+                        );
+                        std::thread::yield_now();
+                    }
+                    Err(e) => panic!("unexpected submit error: {e}"),
+                }
+            }
+        }
+        while ran.load(Ordering::Relaxed) < total {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "frame queue never drained"
+            );
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        release.send(()).unwrap();
+        pool.join_sync().unwrap();
+    }
+
+    #[test]
+    fn test_inflight_tasks_reports_running_labeled_job() {
+        let pool = WorkerPool::new(SchedulerConfig::balanced(2));
+        let (release, gate) = std::sync::mpsc::channel::<()>();
+        let (started_tx, started) = std::sync::mpsc::channel();
+        pool.add_job_labeled(
+            Job::new(move || {
+                started_tx.send(()).unwrap();
+                gate.recv().unwrap();
+            })
+            .with_priority(Priority::Normal),
+            "stuck",
+        )
+        .unwrap();
+        started.recv().unwrap();
+
+        let tasks = pool.inflight_tasks();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].label, Some("stuck"));
+        assert!(matches!(tasks[0].priority, Priority::Normal));
+
+        release.send(()).unwrap();
+        pool.join_sync().unwrap();
+        assert!(pool.inflight_tasks().is_empty());
+    }
+
+    #[test]
+    fn test_reservation_limits_default_and_background_workers() {
+        let cfg = SchedulerConfig::builder()
+            .default_threads(8)
+            .frame_threads(1)
+            .background_threads(2)
+            .reserved_frame_threads(3)
+            .build()
+            .with_reservation(8);
+        assert_eq!(cfg.frame_threads, 3);
+        assert_eq!(cfg.default_threads + cfg.background_threads, 5);
+        assert_eq!(cfg.background_threads, 2);
+    }
+
+    #[test]
+    fn test_awaitable_submit_waits_for_frame_queue_space() {
+        let pool = Arc::new(WorkerPool::new(
+            SchedulerConfig::builder()
+                .frame_threads(1)
+                .frame_queue_capacity(1)
+                .build(),
+        ));
+        let (release, gate) = std::sync::mpsc::channel::<()>();
+        let (started_tx, started) = std::sync::mpsc::channel();
+        let frame = |f: Box<dyn FnOnce() + Send>| Job::new(f).with_priority(Priority::VideoFrame);
+        // Occupy the only frame worker, then fill the only queue slot.
+        pool.add_job(frame(Box::new(move || {
+            started_tx.send(()).unwrap();
+            gate.recv().unwrap();
+        })))
+        .unwrap();
+        started.recv().unwrap();
+        pool.add_job(frame(Box::new(|| {}))).unwrap();
+        assert!(matches!(
+            pool.add_job(frame(Box::new(|| {}))),
+            Err(LunarisError::RenderQueueFull)
+        ));
+
+        let ran = Arc::new(AtomicUsize::new(0));
+        let waiter = {
+            let pool = pool.clone();
+            let ran = ran.clone();
+            std::thread::spawn(move || {
+                futures::executor::block_on(pool.add_job_awaitable(frame(Box::new(move || {
+                    ran.fetch_add(1, Ordering::Relaxed);
+                }))))
+            })
+        };
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(!waiter.is_finished());
+        release.send(()).unwrap();
+        waiter.join().unwrap().unwrap();
+        pool.join_sync().unwrap();
+        assert_eq!(ran.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_workers_never_miss_a_wakeup_under_contention() {
+        const PRODUCERS: usize = 8;
+        const JOBS_PER_PRODUCER: usize = 2_000;
+
+        let pool = Arc::new(WorkerPool::new(
+            SchedulerConfig::builder()
+                .default_threads(3)
+                .frame_threads(3)
+                .background_threads(2)
+                .frame_queue_capacity(8)
+                .build(),
+        ));
+        let counter = Arc::new(AtomicUsize::new(0));
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|i| {
+                let pool = pool.clone();
+                let counter = counter.clone();
+                std::thread::spawn(move || {
+                    for n in 0..JOBS_PER_PRODUCER {
+                        loop {
+                            let priority = match (i + n) % 3 {
+                                0 => Priority::VideoFrame,
+                                1 => Priority::Normal,
+                                _ => Priority::Background,
+                            };
+                            let c = counter.clone();
+                            let job = Job::new(move || {
+                                c.fetch_add(1, Ordering::Relaxed);
+                            })
+                            .with_priority(priority);
+                            match pool.add_job(job) {
+                                Ok(()) => break,
+                                // The tiny frame queue fills up; retry until a worker drains it.
+                                Err(LunarisError::RenderQueueFull) => std::thread::yield_now(),
+                                Err(e) => panic!("unexpected submit error: {e}"),
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+        // Restart the workers mid-stream so stop requests race with pushes as well.
+        pool.reconfigure_threads(2, 4, 1, 2);
+        for producer in producers {
+            producer.join().unwrap();
+        }
+
+        // A lost wakeup leaves jobs queued with every worker parked, so the join never returns.
+        let (tx, rx) = std::sync::mpsc::channel();
+        let joiner = pool.clone();
+        std::thread::spawn(move || tx.send(joiner.join_all()).unwrap());
+        rx.recv_timeout(Duration::from_secs(30))
+            .expect("workers stalled with jobs still queued")
+            .unwrap();
+        assert_eq!(
+            counter.load(Ordering::Relaxed),
+            PRODUCERS * JOBS_PER_PRODUCER
+        );
+    }
 }
@@ -2,9 +2,101 @@ use lunaris_api::plugin::{
     DynGui as ApiGui, DynPlugin as ApiPlugin, PluginContext as ApiPluginContext, PluginReport,
 };
 use lunaris_api::util::error::Result;
+use std::time::Duration;
+use tracing::{Span, info_span};
+
+/// Span entered around every plugin lifecycle call so its log lines carry `[plugin]` context.
+fn plugin_span(name: &'static str) -> Span {
+    info_span!("plugin", name)
+}
+
+/// A plugin's answer when the app is about to quit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShutdownVote {
+    Allow,
+    /// Quit only after the user confirms; the reason is shown to them.
+    NeedsConfirmation(String),
+    /// Do not quit.
+    Deny,
+}
+
+/// What the registry does when a plugin panics. Only effective with `panic = "unwind"`; under
+/// `panic = "abort"` a panic ends the process regardless.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// Leave the plugin down after its first crash.
+    #[default]
+    Never,
+    /// Reset and re-init the plugin, unless it already restarted `max_restarts` times within
+    /// the last `window`.
+    OnCrash { max_restarts: u32, window: Duration },
+    /// Always reset and re-init the plugin.
+    Always,
+}
+
+/// Descriptive information about a plugin, for the plugin manager and compatibility checks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PluginMetadata {
+    pub name: &'static str,
+    /// Semantic version, e.g. `"1.4.0"`.
+    pub version: Option<&'static str>,
+    pub author: Option<&'static str>,
+    pub description: Option<&'static str>,
+    /// Plugins this one needs, by name and the version it was built against. Registering it
+    /// fails unless each is already registered in a compatible version.
+    pub requires: &'static [(&'static str, (u32, u32, u32))],
+}
+
+impl PluginMetadata {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            version: None,
+            author: None,
+            description: None,
+            requires: &[],
+        }
+    }
+
+    /// `version` as `(major, minor, patch)`. Missing components count as `0` and pre-release or
+    /// build suffixes are ignored. `None` if there is no version or it is not numeric.
+    pub fn parsed_version(&self) -> Option<(u32, u32, u32)> {
+        let core = self.version?.split(['-', '+']).next()?;
+        let mut parts = core.split('.').map(str::parse::<u32>);
+        let major = parts.next()?.ok()?;
+        let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+        let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+        Some((major, minor, patch))
+    }
+
+    /// Whether this plugin can stand in where `required` is expected: semver-compatible (same
+    /// major, or same minor while the major is `0`) and not older. Plugins without a parseable
+    /// version are never compatible.
+    pub fn is_compatible_with(&self, required: (u32, u32, u32)) -> bool {
+        let Some(have) = self.parsed_version() else {
+            return false;
+        };
+        let same_line = if required.0 == 0 {
+            have.0 == 0 && have.1 == required.1
+        } else {
+            have.0 == required.0
+        };
+        same_line && have >= required
+    }
+}
 
 pub trait PluginNode: Send + Sync {
     fn name(&self) -> &'static str;
+    /// Defaults to just the name, which is all `GuiPluginNode` has: `GuiRegistration` carries
+    /// nothing else.
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata::new(self.name())
+    }
+    /// Consulted by the registry after the plugin panics. `lunaris_api` plugins have no say;
+    /// `CorePluginNode` reports what their library declared.
+    fn restart_policy(&self) -> RestartPolicy {
+        RestartPolicy::Never
+    }
     fn init(&self, ctx: ApiPluginContext<'_>) -> Result;
     fn update_world(&mut self, ctx: ApiPluginContext<'_>) -> Result;
     fn report(&self, ctx: ApiPluginContext<'_>) -> PluginReport;
@@ -12,39 +104,84 @@ pub trait PluginNode: Send + Sync {
     fn reset(&mut self, ctx: ApiPluginContext<'_>);
     fn register_menu(&self, _menu_bar: &mut lunaris_api::egui::MenuBar) {}
     fn ui(&self, _ui: &mut lunaris_api::egui::Ui, _ctx: ApiPluginContext<'_>) {}
+    /// Contents of the menu shown when the plugin's pane background is right-clicked.
+    fn context_menu(&self, _ui: &mut lunaris_api::egui::Ui, _ctx: ApiPluginContext<'_>) {}
+    /// Persistent chrome in the shared top bar, below the menu. Called once per frame, in
+    /// plugin registration order, laid out left to right.
+    fn top_bar_ui(&self, _ui: &mut lunaris_api::egui::Ui) {}
+    /// Persistent chrome in the shared status bar at the bottom of the window. Called once
+    /// per frame, in plugin registration order, laid out left to right.
+    fn status_bar_ui(&self, _ui: &mut lunaris_api::egui::Ui) {}
+    /// Consulted before the app quits, e.g. to protect unsaved changes.
+    fn can_shutdown(&self, _ctx: ApiPluginContext<'_>) -> ShutdownVote {
+        ShutdownVote::Allow
+    }
     fn is_gui(&self) -> bool {
         false
     }
 }
 
-pub struct CorePluginNode(pub Box<dyn ApiPlugin>);
+pub struct CorePluginNode(
+    pub Box<dyn ApiPlugin>,
+    Option<PluginMetadata>,
+    RestartPolicy,
+);
 impl CorePluginNode {
     pub fn new(inner: Box<dyn ApiPlugin>) -> Self {
-        Self(inner)
+        Self(inner, None, RestartPolicy::Never)
+    }
+    /// Supervise the plugin with `policy` instead of `RestartPolicy::Never`.
+    pub fn with_restart_policy(mut self, policy: RestartPolicy) -> Self {
+        self.2 = policy;
+        self
+    }
+    /// Report `metadata` instead of just the name, e.g. as declared by a plugin library.
+    pub fn with_metadata(mut self, metadata: PluginMetadata) -> Self {
+        self.1 = Some(metadata);
+        self
     }
 }
 impl PluginNode for CorePluginNode {
     fn name(&self) -> &'static str {
         self.0.name()
     }
+    fn metadata(&self) -> PluginMetadata {
+        self.1
+            .clone()
+            .unwrap_or_else(|| PluginMetadata::new(self.name()))
+    }
+    fn restart_policy(&self) -> RestartPolicy {
+        self.2
+    }
     fn init(&self, ctx: ApiPluginContext<'_>) -> Result {
+        let _span = plugin_span(self.name()).entered();
         self.0.init(ctx)
     }
     fn update_world(&mut self, ctx: ApiPluginContext<'_>) -> Result {
+        let _span = plugin_span(self.name()).entered();
         self.0.update_world(ctx)
     }
     fn report(&self, ctx: ApiPluginContext<'_>) -> PluginReport {
+        let _span = plugin_span(self.name()).entered();
         self.0.report(ctx)
     }
     fn shutdown(&mut self, ctx: ApiPluginContext<'_>) {
+        let _span = plugin_span(self.name()).entered();
         self.0.shutdown(ctx)
     }
     fn reset(&mut self, ctx: ApiPluginContext<'_>) {
+        let _span = plugin_span(self.name()).entered();
         self.0.reset(ctx)
     }
     fn register_menu(&self, menu_bar: &mut lunaris_api::egui::MenuBar) {
         self.0.register_menu(menu_bar)
     }
+    fn top_bar_ui(&self, ui: &mut lunaris_api::egui::Ui) {
+        self.0.top_bar_ui(ui)
+    }
+    fn status_bar_ui(&self, ui: &mut lunaris_api::egui::Ui) {
+        self.0.status_bar_ui(ui)
+    }
 }
 
 pub struct GuiPluginNode(pub Box<dyn ApiGui>);
@@ -58,23 +195,34 @@ impl PluginNode for GuiPluginNode {
         self.0.name()
     }
     fn init(&self, ctx: ApiPluginContext<'_>) -> Result {
+        let _span = plugin_span(self.name()).entered();
         self.0.init(ctx)
     }
     fn update_world(&mut self, ctx: ApiPluginContext<'_>) -> Result {
+        let _span = plugin_span(self.name()).entered();
         self.0.update_world(ctx)
     }
     fn report(&self, ctx: ApiPluginContext<'_>) -> PluginReport {
+        let _span = plugin_span(self.name()).entered();
         self.0.report(ctx)
     }
     fn shutdown(&mut self, ctx: ApiPluginContext<'_>) {
+        let _span = plugin_span(self.name()).entered();
         self.0.shutdown(ctx)
     }
     fn reset(&mut self, ctx: ApiPluginContext<'_>) {
+        let _span = plugin_span(self.name()).entered();
         self.0.reset(ctx)
     }
     fn register_menu(&self, menu_bar: &mut lunaris_api::egui::MenuBar) {
         self.0.register_menu(menu_bar)
     }
+    fn top_bar_ui(&self, ui: &mut lunaris_api::egui::Ui) {
+        self.0.top_bar_ui(ui)
+    }
+    fn status_bar_ui(&self, ui: &mut lunaris_api::egui::Ui) {
+        self.0.status_bar_ui(ui)
+    }
     fn ui(&self, ui: &mut lunaris_api::egui::Ui, ctx: ApiPluginContext<'_>) {
         ApiGui::ui(self.0.as_ref(), ui, ctx)
     }
@@ -82,3 +230,36 @@ impl PluginNode for GuiPluginNode {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versioned(version: &'static str) -> PluginMetadata {
+        PluginMetadata {
+            version: Some(version),
+            ..PluginMetadata::new("test")
+        }
+    }
+
+    #[test]
+    fn test_parsed_version() {
+        assert_eq!(versioned("1.4.2").parsed_version(), Some((1, 4, 2)));
+        assert_eq!(versioned("2").parsed_version(), Some((2, 0, 0)));
+        assert_eq!(versioned("0.3.1-beta+7").parsed_version(), Some((0, 3, 1)));
+        assert_eq!(versioned("one").parsed_version(), None);
+        assert_eq!(PluginMetadata::new("test").parsed_version(), None);
+    }
+
+    #[test]
+    fn test_is_compatible_with() {
+        assert!(versioned("1.4.0").is_compatible_with((1, 2, 0)));
+        assert!(versioned("1.4.0").is_compatible_with((1, 4, 0)));
+        assert!(!versioned("1.4.0").is_compatible_with((1, 5, 0)));
+        assert!(!versioned("2.0.0").is_compatible_with((1, 0, 0)));
+        // Below 1.0 every minor release is a breaking one.
+        assert!(versioned("0.3.2").is_compatible_with((0, 3, 1)));
+        assert!(!versioned("0.4.0").is_compatible_with((0, 3, 0)));
+        assert!(!PluginMetadata::new("test").is_compatible_with((0, 0, 0)));
+    }
+}
@@ -1,9 +1,618 @@
-use lunaris_api::plugin::DynPlugin;
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::time::Instant;
 
+use libloading::Library;
+use lunaris_api::plugin::{DynPlugin, PluginContext, PluginReport};
+use lunaris_api::util::error::{LunarisError, Result};
+use lunaris_ecs::prelude::*;
+use tracing::{error, info, warn};
+
+use crate::{
+    consts,
+    oops::Oops,
+    orchestrator::Orchestrator,
+    plugin::{CorePluginNode, PluginMetadata, PluginNode, RestartPolicy, ShutdownVote},
+};
+
+/// Symbol every dynamically loaded plugin library must export, as a `PluginEntryFn`.
+pub const PLUGIN_ENTRY_SYMBOL: &str = "lunaris_plugin_entry";
+
+/// `u32` static every dynamically loaded plugin library must export, equal to the host's
+/// `consts::VERSION_MAJOR`. Read before the entry point runs.
+pub const PLUGIN_ABI_SYMBOL: &str = "lunaris_plugin_abi_version";
+
+/// What a plugin library's entry point hands back.
+#[repr(C)]
+pub struct PluginDeclaration {
+    /// A leaked `Box<dyn DynPlugin>`; the registry takes ownership of it.
+    pub plugin: *mut dyn DynPlugin,
+    /// Reported by `PluginNode::metadata`; `name` should match the plugin's.
+    pub metadata: PluginMetadata,
+    /// How the registry supervises the plugin if it panics.
+    pub restart_policy: RestartPolicy,
+}
+
+/// Signature of `PLUGIN_ENTRY_SYMBOL`. Plugins have to be built with the same compiler as the
+/// host, since `dyn DynPlugin` has no stable layout.
+pub type PluginEntryFn = unsafe extern "Rust" fn() -> PluginDeclaration;
+
+/// Core (non-GUI) plugins ticked by the world thread, in registration order.
+#[derive(Resource, Default)]
 pub struct PluginRegistry {
     inner: Vec<PluginEntry>,
 }
 
 pub struct PluginEntry {
-    inner: Box<dyn DynPlugin>,
+    inner: Box<dyn PluginNode>,
+    /// Set once the plugin crashed and its restart policy gave up on it; it is skipped from
+    /// then on.
+    down: bool,
+    /// When the plugin was restarted, oldest first, for `RestartPolicy::OnCrash` windows.
+    restarts: VecDeque<Instant>,
+    total_restarts: u32,
+    /// Library a dynamically loaded plugin came from. Declared after `inner` so the plugin is
+    /// dropped before its code is unloaded.
+    _library: Option<Library>,
+}
+
+impl PluginEntry {
+    /// Whether `policy` allows another restart now. Forgets restarts older than the window.
+    fn may_restart(&mut self, policy: RestartPolicy, now: Instant) -> bool {
+        match policy {
+            RestartPolicy::Never => false,
+            RestartPolicy::Always => true,
+            RestartPolicy::OnCrash {
+                max_restarts,
+                window,
+            } => {
+                while self
+                    .restarts
+                    .front()
+                    .is_some_and(|at| now.duration_since(*at) > window)
+                {
+                    self.restarts.pop_front();
+                }
+                self.restarts.len() < max_restarts as usize
+            }
+        }
+    }
+
+    /// Handle a panic in this plugin: reset and re-init it if its policy permits, otherwise
+    /// take it down. A panic or error while restarting also takes it down.
+    fn supervise(&mut self, world: &mut World, orch: &Orchestrator) {
+        let name = self.inner.name();
+        let now = Instant::now();
+        if !self.may_restart(self.inner.restart_policy(), now) {
+            error!("Plugin {name} crashed and will stay down.");
+            self.down = true;
+            Oops::new(LunarisError::PluginCrashed {
+                plugin: name.into(),
+            })
+            .notify();
+            return;
+        }
+        self.restarts.push_back(now);
+        self.total_restarts += 1;
+        warn!(
+            "Plugin {name} crashed; restarting (restart #{}).",
+            self.total_restarts
+        );
+        let restarted = panic::catch_unwind(AssertUnwindSafe(|| {
+            self.inner.reset(PluginContext {
+                world: &mut *world,
+                orch,
+            });
+            self.inner.init(PluginContext {
+                world: &mut *world,
+                orch,
+            })
+        }));
+        match restarted {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                error!("Plugin {name} failed to re-init after a crash: {e}");
+                self.down = true;
+            }
+            Err(_) => {
+                error!("Plugin {name} panicked while restarting; leaving it down.");
+                self.down = true;
+            }
+        }
+    }
+}
+
+impl PluginRegistry {
+    /// Register `plugin` after the ones already registered. Fails with `PluginIncompatible`
+    /// if a plugin it `requires` is not registered in a compatible version.
+    pub fn register(&mut self, plugin: Box<dyn PluginNode>) -> Result<u32> {
+        self.check_requirements(plugin.as_ref())?;
+        Ok(self.push(plugin, None))
+    }
+
+    fn check_requirements(&self, plugin: &dyn PluginNode) -> Result {
+        let metadata = plugin.metadata();
+        for &(name, required) in metadata.requires {
+            if self.find_compatible(name, required).is_none() {
+                let (major, minor, patch) = required;
+                error!(
+                    "Plugin {} requires {name} {major}.{minor}.{patch}, which is not registered.",
+                    metadata.name
+                );
+                return Err(LunarisError::PluginIncompatible {
+                    plugin: metadata.name.into(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, plugin: Box<dyn PluginNode>, library: Option<Library>) -> u32 {
+        self.inner.push(PluginEntry {
+            inner: plugin,
+            down: false,
+            restarts: VecDeque::new(),
+            total_restarts: 0,
+            _library: library,
+        });
+        (self.inner.len() - 1) as u32
+    }
+
+    /// Load a plugin from the shared library at `path` through its `PLUGIN_ENTRY_SYMBOL` and
+    /// register it, returning its index in registration order. The library stays loaded as
+    /// long as the plugin is registered.
+    ///
+    /// Fails with `PluginLoadFailed` if the library cannot be opened, `PluginMissingSymbols`
+    /// if it lacks either symbol and `PluginIncompatible` if its `PLUGIN_ABI_SYMBOL` is not
+    /// `consts::VERSION_MAJOR` or, as with `register`, a plugin it requires is missing. The
+    /// entry point only runs once the ABI version matches. The plugin is not `init`ed here.
+    pub fn load_from_path(&mut self, path: &Path) -> Result<u32> {
+        let shown = path.display().to_string();
+        // SAFETY: loading runs the library's initializers; plugin libraries are trusted code.
+        let library = unsafe { Library::new(path) }.map_err(|e| {
+            error!("Could not load plugin library {shown}: {e}");
+            LunarisError::PluginLoadFailed {
+                plugin: shown.clone(),
+                reason: e.to_string(),
+            }
+        })?;
+        // SAFETY: the symbol is declared as a `u32` static.
+        let abi_version = match unsafe { library.get::<*const u32>(PLUGIN_ABI_SYMBOL.as_bytes()) } {
+            Ok(version) => unsafe { **version },
+            Err(e) => {
+                error!("Plugin library {shown} does not export {PLUGIN_ABI_SYMBOL}: {e}");
+                return Err(LunarisError::PluginMissingSymbols { plugin: shown });
+            }
+        };
+        if abi_version != consts::VERSION_MAJOR {
+            error!(
+                "Plugin library {shown} targets ABI {abi_version}, the host is {}.",
+                consts::VERSION_MAJOR
+            );
+            return Err(LunarisError::PluginIncompatible { plugin: shown });
+        }
+        // SAFETY: the symbol is declared to have `PluginEntryFn`'s signature.
+        let entry = match unsafe { library.get::<PluginEntryFn>(PLUGIN_ENTRY_SYMBOL.as_bytes()) } {
+            Ok(entry) => *entry,
+            Err(e) => {
+                error!("Plugin library {shown} does not export {PLUGIN_ENTRY_SYMBOL}: {e}");
+                return Err(LunarisError::PluginMissingSymbols { plugin: shown });
+            }
+        };
+        // SAFETY: see `PluginEntryFn`; the library was built for this ABI.
+        let declaration = unsafe { entry() };
+        if declaration.plugin.is_null() {
+            return Err(LunarisError::PluginLoadFailed {
+                plugin: shown,
+                reason: format!("{PLUGIN_ENTRY_SYMBOL} returned no plugin"),
+            });
+        }
+        // SAFETY: the entry point hands over a leaked box, built against the same ABI.
+        let plugin = unsafe { Box::from_raw(declaration.plugin) };
+        info!("Loaded plugin {} from {shown}.", plugin.name());
+        let node = CorePluginNode::new(plugin)
+            .with_metadata(declaration.metadata)
+            .with_restart_policy(declaration.restart_policy);
+        // Rejected here rather than in `push` so the plugin is dropped before its library.
+        self.check_requirements(&node)?;
+        Ok(self.push(Box::new(node), Some(library)))
+    }
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Metadata of every registered plugin, in registration order.
+    pub fn metadata(&self) -> impl Iterator<Item = PluginMetadata> + '_ {
+        self.inner.iter().map(|entry| entry.inner.metadata())
+    }
+
+    /// Metadata of the plugin called `name` if it is registered and compatible with
+    /// `required`; see `PluginMetadata::is_compatible_with`.
+    pub fn find_compatible(&self, name: &str, required: (u32, u32, u32)) -> Option<PluginMetadata> {
+        self.metadata()
+            .find(|meta| meta.name == name)
+            .filter(|meta| meta.is_compatible_with(required))
+    }
+
+    /// Run `init` on every plugin. One plugin failing or panicking does not stop the rest;
+    /// every outcome is returned in registration order, a panic as `PluginCrashed`.
+    pub fn init_all(&self, world: &mut World, orch: &Orchestrator) -> PluginResults {
+        self.inner
+            .iter()
+            .map(|entry| {
+                let name = entry.inner.name();
+                let result = guarded(name, || {
+                    entry.inner.init(PluginContext {
+                        world: &mut *world,
+                        orch,
+                    })
+                });
+                (name, result)
+            })
+            .collect()
+    }
+
+    /// Run `update_world` on every plugin that is not down, with the same continue-on-error
+    /// semantics as `init_all`. A panicking plugin is reported as `PluginCrashed` and then
+    /// restarted or taken down according to its `RestartPolicy`.
+    pub fn update_all(&mut self, world: &mut World, orch: &Orchestrator) -> PluginResults {
+        let mut results = Vec::with_capacity(self.inner.len());
+        for entry in self.inner.iter_mut().filter(|entry| !entry.down) {
+            let name = entry.inner.name();
+            let updated = panic::catch_unwind(AssertUnwindSafe(|| {
+                entry.inner.update_world(PluginContext {
+                    world: &mut *world,
+                    orch,
+                })
+            }));
+            match updated {
+                Ok(result) => results.push((name, result)),
+                Err(_) => {
+                    results.push((
+                        name,
+                        Err(LunarisError::PluginCrashed {
+                            plugin: name.into(),
+                        }),
+                    ));
+                    entry.supervise(world, orch);
+                }
+            }
+        }
+        results
+    }
+
+    /// Plugins that crashed and were not restarted, in registration order.
+    pub fn down(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.inner
+            .iter()
+            .filter(|entry| entry.down)
+            .map(|entry| entry.inner.name())
+    }
+
+    /// `can_shutdown` of every plugin that is not down, in registration order.
+    pub fn shutdown_votes(
+        &self,
+        world: &mut World,
+        orch: &Orchestrator,
+    ) -> Vec<(&'static str, ShutdownVote)> {
+        self.inner
+            .iter()
+            .filter(|entry| !entry.down)
+            .map(|entry| {
+                let ctx = PluginContext {
+                    world: &mut *world,
+                    orch,
+                };
+                (entry.inner.name(), entry.inner.can_shutdown(ctx))
+            })
+            .collect()
+    }
+
+    /// Run `shutdown` on every plugin, with the same continue-on-panic semantics as
+    /// `init_all`.
+    pub fn shutdown_all(&mut self, world: &mut World, orch: &Orchestrator) -> PluginResults {
+        self.inner
+            .iter_mut()
+            .map(|entry| {
+                let name = entry.inner.name();
+                let result = guarded(name, || {
+                    entry.inner.shutdown(PluginContext {
+                        world: &mut *world,
+                        orch,
+                    });
+                    Ok(())
+                });
+                (name, result)
+            })
+            .collect()
+    }
+
+    /// Run `reset` on every plugin, with the same continue-on-panic semantics as `init_all`.
+    pub fn reset_all(&mut self, world: &mut World, orch: &Orchestrator) -> PluginResults {
+        self.inner
+            .iter_mut()
+            .map(|entry| {
+                let name = entry.inner.name();
+                let result = guarded(name, || {
+                    entry.inner.reset(PluginContext {
+                        world: &mut *world,
+                        orch,
+                    });
+                    Ok(())
+                });
+                (name, result)
+            })
+            .collect()
+    }
+}
+
+/// Run a lifecycle call of plugin `name`, turning a panic into `PluginCrashed`.
+fn guarded(name: &'static str, f: impl FnOnce() -> Result) -> Result {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| {
+        Err(LunarisError::PluginCrashed {
+            plugin: name.into(),
+        })
+    })
+}
+
+/// Per-plugin outcomes of a bulk operation, by plugin name, in registration order.
+pub type PluginResults = Vec<(&'static str, Result)>;
+
+/// Log every failure in `results`, returning how many there were.
+pub fn log_failures(operation: &str, results: &PluginResults) -> usize {
+    let mut failures = 0;
+    for (name, result) in results {
+        if let Err(e) = result {
+            error!("Plugin {name} failed to {operation}: {e}");
+            failures += 1;
+        }
+    }
+    failures
+}
+
+/// Reports gathered by `collect_reports` on the latest tick, in registration order.
+#[derive(Resource, Default)]
+pub struct PluginReports {
+    pub reports: Vec<(&'static str, PluginReport)>,
+}
+
+/// Take the registry and orchestrator out of `world` for the duration of `f`, so plugins can
+/// be handed `&mut World` alongside them.
+pub(crate) fn with_plugins(
+    world: &mut World,
+    f: impl FnOnce(&mut World, &Orchestrator, &mut PluginRegistry),
+) {
+    world.resource_scope(|world, orch: Mut<Orchestrator>| {
+        world.resource_scope(|world, mut registry: Mut<PluginRegistry>| {
+            f(world, &orch, &mut registry)
+        })
+    })
+}
+
+/// Run `init` on every registered plugin.
+pub fn init_plugins(world: &mut World) {
+    with_plugins(world, |world, orch, registry| {
+        log_failures("init", &registry.init_all(world, orch));
+    });
+}
+
+/// Run `update_world` on every registered plugin.
+pub fn tick_plugins(world: &mut World) {
+    with_plugins(world, |world, orch, registry| {
+        log_failures("update", &registry.update_all(world, orch));
+    });
+}
+
+/// Replace `PluginReports` with a fresh report from every registered plugin.
+pub fn collect_reports(world: &mut World) {
+    let mut reports = Vec::new();
+    with_plugins(world, |world, orch, registry| {
+        for entry in registry.inner.iter().filter(|entry| !entry.down) {
+            let ctx = PluginContext {
+                world: &mut *world,
+                orch,
+            };
+            reports.push((entry.inner.name(), entry.inner.report(ctx)));
+        }
+    });
+    world.resource_mut::<PluginReports>().reports = reports;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::worker::SchedulerConfig;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Panics on every update and counts how often it was (re-)initialized.
+    struct Crashy(Arc<AtomicUsize>);
+
+    impl PluginNode for Crashy {
+        fn name(&self) -> &'static str {
+            "crashy"
+        }
+        fn init(&self, _ctx: PluginContext<'_>) -> Result {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+        fn update_world(&mut self, _ctx: PluginContext<'_>) -> Result {
+            panic!("crashy crashed");
+        }
+        fn report(&self, _ctx: PluginContext<'_>) -> PluginReport {
+            unreachable!("not called in this test")
+        }
+        fn shutdown(&mut self, _ctx: PluginContext<'_>) {}
+        fn reset(&mut self, _ctx: PluginContext<'_>) {}
+        fn restart_policy(&self) -> RestartPolicy {
+            RestartPolicy::OnCrash {
+                max_restarts: 2,
+                window: Duration::from_secs(60),
+            }
+        }
+    }
+
+    /// Asks for confirmation before quitting.
+    struct Unsaved;
+
+    impl PluginNode for Unsaved {
+        fn name(&self) -> &'static str {
+            "unsaved"
+        }
+        fn init(&self, _ctx: PluginContext<'_>) -> Result {
+            Ok(())
+        }
+        fn update_world(&mut self, _ctx: PluginContext<'_>) -> Result {
+            Ok(())
+        }
+        fn report(&self, _ctx: PluginContext<'_>) -> PluginReport {
+            unreachable!("not called in this test")
+        }
+        fn shutdown(&mut self, _ctx: PluginContext<'_>) {}
+        fn reset(&mut self, _ctx: PluginContext<'_>) {}
+        fn can_shutdown(&self, _ctx: PluginContext<'_>) -> ShutdownVote {
+            ShutdownVote::NeedsConfirmation("unsaved edits".into())
+        }
+    }
+
+    // Loading real plugin libraries is covered by `tests/plugin_loading.rs`.
+    #[test]
+    fn test_load_from_path_rejects_unloadable_libraries() {
+        let mut registry = PluginRegistry::default();
+        assert!(matches!(
+            registry.load_from_path(Path::new("/nonexistent/libplugin.so")),
+            Err(LunarisError::PluginLoadFailed { .. })
+        ));
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_shutdown_votes_poll_registered_plugins() {
+        let mut registry = PluginRegistry::default();
+        registry.register(Box::new(Unsaved)).unwrap();
+        let mut world = World::new();
+        let orch = Orchestrator::with_config(SchedulerConfig::single_threaded());
+
+        assert_eq!(
+            registry.shutdown_votes(&mut world, &orch),
+            [(
+                "unsaved",
+                ShutdownVote::NeedsConfirmation("unsaved edits".into())
+            )]
+        );
+    }
+
+    /// Reports a name, a version and what it requires.
+    struct Versioned(
+        &'static str,
+        &'static str,
+        &'static [(&'static str, (u32, u32, u32))],
+    );
+
+    impl PluginNode for Versioned {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+        fn metadata(&self) -> PluginMetadata {
+            PluginMetadata {
+                version: Some(self.1),
+                requires: self.2,
+                ..PluginMetadata::new(self.0)
+            }
+        }
+        fn init(&self, _ctx: PluginContext<'_>) -> Result {
+            Ok(())
+        }
+        fn update_world(&mut self, _ctx: PluginContext<'_>) -> Result {
+            Ok(())
+        }
+        fn report(&self, _ctx: PluginContext<'_>) -> PluginReport {
+            unreachable!("not called in this test")
+        }
+        fn shutdown(&mut self, _ctx: PluginContext<'_>) {}
+        fn reset(&mut self, _ctx: PluginContext<'_>) {}
+    }
+
+    #[test]
+    fn test_register_checks_required_versions() {
+        const NEEDS_CORE_1_2: &[(&str, (u32, u32, u32))] = &[("core", (1, 2, 0))];
+        let dependent = || Box::new(Versioned("dependent", "0.1.0", NEEDS_CORE_1_2));
+
+        let mut registry = PluginRegistry::default();
+        assert!(matches!(
+            registry.register(dependent()),
+            Err(LunarisError::PluginIncompatible { .. })
+        ));
+        registry
+            .register(Box::new(Versioned("core", "1.1.0", &[])))
+            .unwrap();
+        assert!(registry.register(dependent()).is_err());
+
+        let mut registry = PluginRegistry::default();
+        registry
+            .register(Box::new(Versioned("core", "1.4.0", &[])))
+            .unwrap();
+        assert_eq!(registry.register(dependent()).unwrap(), 1);
+    }
+
+    /// Panics when shut down.
+    struct Stubborn;
+
+    impl PluginNode for Stubborn {
+        fn name(&self) -> &'static str {
+            "stubborn"
+        }
+        fn init(&self, _ctx: PluginContext<'_>) -> Result {
+            Ok(())
+        }
+        fn update_world(&mut self, _ctx: PluginContext<'_>) -> Result {
+            Ok(())
+        }
+        fn report(&self, _ctx: PluginContext<'_>) -> PluginReport {
+            unreachable!("not called in this test")
+        }
+        fn shutdown(&mut self, _ctx: PluginContext<'_>) {
+            panic!("stubborn refused to shut down");
+        }
+        fn reset(&mut self, _ctx: PluginContext<'_>) {}
+    }
+
+    #[test]
+    fn test_shutdown_all_continues_past_a_panic() {
+        let mut registry = PluginRegistry::default();
+        registry.register(Box::new(Stubborn)).unwrap();
+        registry.register(Box::new(Unsaved)).unwrap();
+        let mut world = World::new();
+        let orch = Orchestrator::with_config(SchedulerConfig::single_threaded());
+
+        let results = registry.shutdown_all(&mut world, &orch);
+        assert!(matches!(
+            results[0],
+            ("stubborn", Err(LunarisError::PluginCrashed { .. }))
+        ));
+        assert!(matches!(results[1], ("unsaved", Ok(()))));
+    }
+
+    #[test]
+    fn test_crashed_plugin_restarts_until_budget_is_spent() {
+        let inits = Arc::new(AtomicUsize::new(0));
+        let mut registry = PluginRegistry::default();
+        registry.register(Box::new(Crashy(inits.clone()))).unwrap();
+        let mut world = World::new();
+        let orch = Orchestrator::with_config(SchedulerConfig::single_threaded());
+
+        for _ in 0..4 {
+            let results = registry.update_all(&mut world, &orch);
+            assert!(results.iter().all(|(_, result)| result.is_err()));
+        }
+        // Two restarts within the window, then the plugin stays down and is skipped.
+        assert_eq!(inits.load(Ordering::Relaxed), 2);
+        assert_eq!(registry.down().collect::<Vec<_>>(), ["crashy"]);
+        assert!(registry.update_all(&mut world, &orch).is_empty());
+    }
 }
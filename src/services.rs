@@ -0,0 +1,85 @@
+//! Named, typed services shared between plugins through the world, e.g. a renderer
+//! publishing its device for a viewport to use.
+
+use std::{any::Any, sync::Arc};
+
+use dashmap::DashMap;
+use lunaris_ecs::prelude::*;
+use tracing::warn;
+
+/// Service registry resource. Plugins reach it through `PluginContext::world`:
+/// `ctx.world.resource::<Services>().get_service::<AssetCache>("assets")`.
+#[derive(Resource, Default)]
+pub struct Services {
+    services: DashMap<String, Arc<dyn Any + Send + Sync>>,
+}
+
+impl Services {
+    /// Publish `service` under `name`, replacing (and returning) any previous one.
+    pub fn register_service<T: Any + Send + Sync>(
+        &self,
+        name: impl Into<String>,
+        service: Arc<T>,
+    ) -> Option<Arc<dyn Any + Send + Sync>> {
+        let name = name.into();
+        let previous = self.services.insert(name.clone(), service);
+        if previous.is_some() {
+            warn!("Service {name} was registered twice; replacing it.");
+        }
+        previous
+    }
+
+    /// The service called `name`, if one is registered and it is a `T`.
+    pub fn get_service<T: Any + Send + Sync>(&self, name: &str) -> Option<Arc<T>> {
+        let service = self.services.get(name)?.value().clone();
+        service.downcast::<T>().ok()
+    }
+
+    pub fn unregister_service(&self, name: &str) -> Option<Arc<dyn Any + Send + Sync>> {
+        self.services.remove(name).map(|(_, service)| service)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_returns_registered_service() {
+        let services = Services::default();
+        assert!(services.get_service::<u32>("answer").is_none());
+        assert!(
+            services
+                .register_service("answer", Arc::new(42u32))
+                .is_none()
+        );
+        assert_eq!(services.get_service::<u32>("answer").as_deref(), Some(&42));
+    }
+
+    #[test]
+    fn test_get_with_wrong_type_is_none() {
+        let services = Services::default();
+        services.register_service("answer", Arc::new(42u32));
+        assert!(services.get_service::<String>("answer").is_none());
+        // The mismatch does not disturb the registered service.
+        assert!(services.get_service::<u32>("answer").is_some());
+    }
+
+    #[test]
+    fn test_register_replaces_and_unregister_removes() {
+        let services = Services::default();
+        services.register_service("name", Arc::new("old".to_string()));
+        let previous = services.register_service("name", Arc::new("new".to_string()));
+        let previous = previous.and_then(|p| p.downcast::<String>().ok());
+        assert_eq!(previous.as_deref().map(String::as_str), Some("old"));
+        assert_eq!(
+            services
+                .get_service::<String>("name")
+                .as_deref()
+                .map(String::as_str),
+            Some("new")
+        );
+        assert!(services.unregister_service("name").is_some());
+        assert!(services.get_service::<String>("name").is_none());
+    }
+}
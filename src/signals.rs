@@ -1,36 +1,200 @@
-use std::process::{abort, exit};
+use std::{
+    panic,
+    process::{abort, exit},
+    sync::{
+        Arc, Once,
+        atomic::{AtomicU64, Ordering},
+        mpsc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
 
 use lunaris_api::util::error::{LunarisError, Result};
 use native_dialog::DialogBuilder;
+use parking_lot::Mutex;
 use signal_hook::{
     consts::{SIGABRT, SIGINT},
-    low_level::register,
+    iterator::{Handle, Signals},
+    low_level::emulate_default_handler,
 };
 use tracing::*;
 
+use crate::{
+    kernel, logging,
+    oops::{dialogs_enabled, panic_message},
+};
+
+/// Callback run on SIGINT before the process exits; see `on_shutdown`.
+pub type ShutdownHook = Arc<dyn Fn() + Send + Sync>;
+
+/// How long all shutdown hooks together may take before SIGINT falls back to `abort`.
+pub const SHUTDOWN_HOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Registered hooks with the id their `ShutdownHookHandle` removes them by.
+static SHUTDOWN_HOOKS: Mutex<Vec<(u64, ShutdownHook)>> = Mutex::new(Vec::new());
+static NEXT_HOOK_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Run `hook` when SIGINT arrives, after hooks registered before it, until the returned
+/// handle is dropped. Hooks run on a regular thread, not in the signal handler, so they may
+/// block and allocate. `kernel::is_shutting_down` is already `true` when they run.
+pub fn on_shutdown(hook: ShutdownHook) -> ShutdownHookHandle {
+    let id = NEXT_HOOK_ID.fetch_add(1, Ordering::Relaxed);
+    SHUTDOWN_HOOKS.lock().push((id, hook));
+    ShutdownHookHandle(id)
+}
+
+/// Keeps a hook registered with `on_shutdown`; dropping it unregisters the hook.
+#[must_use = "the shutdown hook is unregistered when the handle is dropped"]
+pub struct ShutdownHookHandle(u64);
+
+impl Drop for ShutdownHookHandle {
+    fn drop(&mut self) {
+        SHUTDOWN_HOOKS.lock().retain(|(id, _)| *id != self.0);
+    }
+}
+
+/// The signal watcher started by `register_hooks`, so `unregister_hooks` can stop it.
+struct Watcher {
+    signals: Handle,
+    thread: JoinHandle<()>,
+}
+
+static WATCHER: Mutex<Option<Watcher>> = Mutex::new(None);
+
+/// Route SIGINT and SIGABRT to a watcher thread and install the logging panic hook. Calling
+/// it again while the hooks are registered is a no-op.
 pub fn register_hooks() -> Result {
-    unsafe {
-        register(SIGINT, || {
-            let _ = DialogBuilder::message()
-                .set_title("SIGINT")
-                .set_text("Received SIGINT. Aborting program.")
-                .set_level(native_dialog::MessageLevel::Error)
-                .alert()
-                .show();
-            error!("SIGINT Received; Attempting to save...");
-            error!("SIGINT not implemented.");
-            abort();
-        })
-        .map_err(|e| LunarisError::KernelInitFailed {
+    let mut watcher = WATCHER.lock();
+    if watcher.is_some() {
+        return Ok(());
+    }
+    let dialogs = dialogs_enabled();
+    // The handler behind `Signals` only records the signal; the watcher thread reacts to it,
+    // so logging and flushing never happen inside a signal handler.
+    let mut signals =
+        Signals::new([SIGINT, SIGABRT]).map_err(|e| LunarisError::KernelInitFailed {
             reason: format!("{e}"),
         })?;
-        register(SIGABRT, || {
-            error!("Aborting(SIGABRT)");
-            exit(1)
+    let handle = signals.handle();
+    let thread = thread::Builder::new()
+        .name("lunaris-signals".into())
+        .spawn(move || {
+            for signal in signals.forever() {
+                match signal {
+                    SIGINT => graceful_shutdown(dialogs),
+                    // Best-effort, for a SIGABRT sent from outside: our own `abort` calls
+                    // flush before aborting and die before this thread gets to run.
+                    _ => {
+                        kernel::begin_shutdown();
+                        error!("Aborting(SIGABRT)");
+                        logging::finish();
+                        let _ = emulate_default_handler(SIGABRT);
+                        abort()
+                    }
+                }
+            }
         })
         .map_err(|e| LunarisError::KernelInitFailed {
             reason: format!("{e}"),
         })?;
-    }
+    *watcher = Some(Watcher {
+        signals: handle,
+        thread,
+    });
+    install_panic_hook();
     Ok(())
 }
+
+/// Undo `register_hooks` and forget every `on_shutdown` hook, for `kernel::clear_all`. The
+/// panic hook stays installed; it is only ever installed once.
+pub fn unregister_hooks() {
+    if let Some(watcher) = WATCHER.lock().take() {
+        watcher.signals.close();
+        let _ = watcher.thread.join();
+    }
+    SHUTDOWN_HOOKS.lock().clear();
+}
+
+/// Run the shutdown hooks and exit, or abort if they take longer than `SHUTDOWN_HOOK_TIMEOUT`.
+fn graceful_shutdown(dialogs: bool) {
+    kernel::begin_shutdown();
+    warn!("SIGINT received; shutting down...");
+    let (done, finished) = mpsc::channel();
+    thread::spawn(move || {
+        // Run outside the lock, so a hook may drop its own or another hook's handle.
+        let hooks: Vec<ShutdownHook> = SHUTDOWN_HOOKS
+            .lock()
+            .iter()
+            .map(|(_, hook)| hook.clone())
+            .collect();
+        for hook in hooks {
+            hook();
+        }
+        let _ = done.send(());
+    });
+    if finished.recv_timeout(SHUTDOWN_HOOK_TIMEOUT).is_ok() {
+        info!("Shutdown hooks finished.");
+        logging::finish();
+        exit(130);
+    }
+    error!("Shutdown hooks did not finish within {SHUTDOWN_HOOK_TIMEOUT:?}; aborting.");
+    if dialogs {
+        let _ = DialogBuilder::message()
+            .set_title("SIGINT")
+            .set_text("Lunaris did not shut down in time after SIGINT. Aborting program.")
+            .set_level(native_dialog::MessageLevel::Error)
+            .alert()
+            .show();
+    }
+    logging::finish();
+    abort();
+}
+
+/// Log panics through tracing (so they land wherever the logs go) and flush before the
+/// previous hook runs, which may abort the process under `panic = "abort"`.
+fn install_panic_hook() {
+    static INSTALLED: Once = Once::new();
+    INSTALLED.call_once(install_panic_hook_once);
+}
+
+fn install_panic_hook_once() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let message = panic_message(info.payload());
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}", l.file(), l.line()))
+            .unwrap_or_else(|| "?".into());
+        let thread = thread::current();
+        error!(
+            "Thread '{}' panicked at {location}: {message}",
+            thread.name().unwrap_or("<unnamed>")
+        );
+        if crate::consts::PANIC_UNWIND {
+            logging::flush();
+        } else {
+            // The previous hook is about to abort the process.
+            logging::finish();
+        }
+        previous(info);
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_registered(id: u64) -> bool {
+        SHUTDOWN_HOOKS.lock().iter().any(|(other, _)| *other == id)
+    }
+
+    #[test]
+    fn test_dropping_the_handle_unregisters_the_hook() {
+        let handle = on_shutdown(Arc::new(|| {}));
+        let id = handle.0;
+        assert!(is_registered(id));
+        drop(handle);
+        assert!(!is_registered(id));
+    }
+}
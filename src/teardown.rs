@@ -0,0 +1,125 @@
+//! Explicit shutdown order for the world thread, instead of whatever order Rust happens to
+//! drop the world's resources in:
+//!
+//! 1. plugins (`PluginNode::shutdown`, with the orchestrator still available),
+//! 2. world systems (the schedule),
+//! 3. the orchestrator (outstanding jobs are joined, then its threads stopped),
+//! 4. the mailbox, which is owned and torn down by `lunaris_api`,
+//! 5. the runtime's GPU handles.
+//!
+//! Each step is announced with an event on `TEARDOWN_TARGET`.
+
+use lunaris_ecs::prelude::*;
+use tracing::{error, info};
+
+use crate::{
+    gpu,
+    orchestrator::Orchestrator,
+    registry::{PluginRegistry, log_failures, with_plugins},
+};
+
+pub const TEARDOWN_TARGET: &str = "lunaris::teardown";
+
+/// Tear down everything the world thread owns, in the order above. `world` keeps only
+/// passive resources afterwards and can be dropped in any order.
+pub fn shutdown(world: &mut World, schedule: Schedule) {
+    info!(target: TEARDOWN_TARGET, step = "plugins", "Shutting down plugins");
+    if world.contains_resource::<PluginRegistry>() && world.contains_resource::<Orchestrator>() {
+        with_plugins(world, |world, orch, registry| {
+            log_failures("shut down", &registry.shutdown_all(world, orch));
+        });
+    }
+    world.remove_resource::<PluginRegistry>();
+
+    info!(target: TEARDOWN_TARGET, step = "systems", "Dropping world systems");
+    drop(schedule);
+
+    info!(target: TEARDOWN_TARGET, step = "orchestrator", "Stopping the orchestrator");
+    if let Some(orch) = world.remove_resource::<Orchestrator>()
+        && let Err(e) = orch.shutdown()
+    {
+        error!("Orchestrator did not drain cleanly: {e}");
+    }
+
+    info!(target: TEARDOWN_TARGET, step = "gpu", "Releasing GPU handles");
+    gpu::release();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{orchestrator::worker::SchedulerConfig, plugin::PluginNode};
+    use lunaris_api::plugin::{PluginContext, PluginReport};
+    use lunaris_api::util::error::Result;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+    use tracing::field::{Field, Visit};
+    use tracing_subscriber::{Layer, layer::Context, prelude::*};
+
+    /// Records the `step` field of every teardown event, in order.
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl<S: tracing::Subscriber> Layer<S> for Recorder {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            struct Step(Option<String>);
+            impl Visit for Step {
+                fn record_str(&mut self, field: &Field, value: &str) {
+                    if field.name() == "step" {
+                        self.0 = Some(value.to_string());
+                    }
+                }
+                fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+            }
+            if event.metadata().target() != TEARDOWN_TARGET {
+                return;
+            }
+            let mut step = Step(None);
+            event.record(&mut step);
+            if let Some(step) = step.0 {
+                self.0.lock().push(step);
+            }
+        }
+    }
+
+    struct TestPlugin;
+
+    impl PluginNode for TestPlugin {
+        fn name(&self) -> &'static str {
+            "test"
+        }
+        fn init(&self, _ctx: PluginContext<'_>) -> Result {
+            Ok(())
+        }
+        fn update_world(&mut self, _ctx: PluginContext<'_>) -> Result {
+            Ok(())
+        }
+        fn report(&self, _ctx: PluginContext<'_>) -> PluginReport {
+            unreachable!("not called during teardown")
+        }
+        fn shutdown(&mut self, _ctx: PluginContext<'_>) {
+            info!(target: TEARDOWN_TARGET, step = "plugin:test");
+        }
+        fn reset(&mut self, _ctx: PluginContext<'_>) {}
+    }
+
+    #[test]
+    fn test_teardown_order() {
+        let steps = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::registry().with(Recorder(steps.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let mut world = World::new();
+            let mut registry = PluginRegistry::default();
+            registry.register(Box::new(TestPlugin)).unwrap();
+            world.insert_resource(registry);
+            world.insert_resource(Orchestrator::with_config(SchedulerConfig::single_threaded()));
+            shutdown(&mut world, Schedule::default());
+            assert!(!world.contains_resource::<Orchestrator>());
+        });
+
+        assert_eq!(
+            *steps.lock(),
+            ["plugins", "plugin:test", "systems", "orchestrator", "gpu"]
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Bounded undo/redo history for editor-style plugins.
+
+use std::collections::VecDeque;
+
+use lunaris_ecs::prelude::*;
+
+use crate::plugin::ShutdownVote;
+
+/// Default number of snapshots kept by `UndoStack::default`.
+pub const DEFAULT_UNDO_DEPTH: usize = 100;
+
+/// A linear history of snapshots with a cursor on the current one. Pushing after an undo
+/// discards the redo branch; once more than `depth` snapshots are held the oldest is dropped.
+///
+/// Also tracks whether the current snapshot differs from the last one marked saved, so a
+/// plugin's `can_shutdown` can simply return `shutdown_vote`.
+#[derive(Resource)]
+pub struct UndoStack<T: Send + Sync + 'static> {
+    /// Snapshots with the generation they were pushed at, oldest first.
+    entries: VecDeque<(u64, T)>,
+    /// Index of the current snapshot in `entries`; meaningless while `entries` is empty.
+    cursor: usize,
+    depth: usize,
+    next_generation: u64,
+    /// Generation of the snapshot that was current at the last `mark_saved`; `None` means the
+    /// empty history was saved.
+    saved: Option<u64>,
+}
+
+impl<T: Send + Sync + 'static> Default for UndoStack<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_UNDO_DEPTH)
+    }
+}
+
+impl<T: Send + Sync + 'static> UndoStack<T> {
+    /// Keep at most `depth` snapshots (at least one).
+    pub fn new(depth: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            cursor: 0,
+            depth: depth.max(1),
+            next_generation: 0,
+            saved: None,
+        }
+    }
+
+    /// Record `snapshot` as the new current state.
+    pub fn push(&mut self, snapshot: T) {
+        if !self.entries.is_empty() {
+            self.entries.truncate(self.cursor + 1);
+        }
+        self.entries.push_back((self.next_generation, snapshot));
+        self.next_generation += 1;
+        if self.entries.len() > self.depth {
+            self.entries.pop_front();
+        }
+        self.cursor = self.entries.len() - 1;
+    }
+
+    /// Step back one snapshot and return it, or `None` at the oldest one.
+    pub fn undo(&mut self) -> Option<&T> {
+        if !self.can_undo() {
+            return None;
+        }
+        self.cursor -= 1;
+        self.current()
+    }
+
+    /// Step forward one snapshot and return it, or `None` at the newest one.
+    pub fn redo(&mut self) -> Option<&T> {
+        if !self.can_redo() {
+            return None;
+        }
+        self.cursor += 1;
+        self.current()
+    }
+
+    pub fn current(&self) -> Option<&T> {
+        self.entries.get(self.cursor).map(|(_, snapshot)| snapshot)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.entries.is_empty() && self.cursor > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.cursor + 1 < self.entries.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drop all history; the (now empty) state counts as saved.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.cursor = 0;
+        self.saved = None;
+    }
+
+    /// Remember the current snapshot as the saved one.
+    pub fn mark_saved(&mut self) {
+        self.saved = self.current_generation();
+    }
+
+    /// `true` if the current snapshot is not the one last marked saved, including after
+    /// undoing past a save.
+    pub fn is_dirty(&self) -> bool {
+        self.current_generation() != self.saved
+    }
+
+    /// `ShutdownVote` for `PluginNode::can_shutdown`: asks for confirmation with `reason`
+    /// while there are unsaved changes.
+    pub fn shutdown_vote(&self, reason: impl Into<String>) -> ShutdownVote {
+        if self.is_dirty() {
+            ShutdownVote::NeedsConfirmation(reason.into())
+        } else {
+            ShutdownVote::Allow
+        }
+    }
+
+    fn current_generation(&self) -> Option<u64> {
+        self.entries
+            .get(self.cursor)
+            .map(|(generation, _)| *generation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undo_redo_and_dirty_tracking() {
+        let mut stack = UndoStack::new(3);
+        assert!(!stack.is_dirty());
+        for n in 0..5 {
+            stack.push(n);
+        }
+        // Only the newest three snapshots are kept.
+        assert_eq!(stack.len(), 3);
+        assert_eq!(stack.undo(), Some(&3));
+        assert_eq!(stack.undo(), Some(&2));
+        assert_eq!(stack.undo(), None);
+        assert_eq!(stack.redo(), Some(&3));
+
+        stack.mark_saved();
+        assert!(!stack.is_dirty());
+        // Pushing after an undo discards the redo branch.
+        stack.push(10);
+        assert!(!stack.can_redo());
+        assert!(stack.is_dirty());
+        assert_eq!(stack.undo(), Some(&3));
+        assert_eq!(stack.shutdown_vote("unsaved"), ShutdownVote::Allow);
+    }
+}
@@ -0,0 +1,50 @@
+//! Shareable workspace files: a config plus a tile layout, loaded with `--workspace <path>`
+//! or `LUNARIS_WORKSPACE` in place of the per-user config.
+
+use std::{env, fs, path::PathBuf};
+
+use egui_tiles::Tree;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::{bridge::PluginId, config::LunarisConfig};
+
+#[derive(Serialize, Deserialize)]
+pub struct Workspace {
+    #[serde(default)]
+    pub config: LunarisConfig,
+    /// Repaired against the installed plugins on load, like a layout from eframe storage.
+    #[serde(default)]
+    pub layout: Option<Tree<PluginId>>,
+}
+
+impl Workspace {
+    /// The `--workspace <path>` (or `--workspace=<path>`) argument, else `LUNARIS_WORKSPACE`.
+    pub fn path() -> Option<PathBuf> {
+        let mut args = env::args_os().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == "--workspace" {
+                return args.next().map(PathBuf::from);
+            }
+            if let Some(path) = arg.to_str().and_then(|a| a.strip_prefix("--workspace=")) {
+                return Some(PathBuf::from(path));
+            }
+        }
+        env::var_os("LUNARIS_WORKSPACE").map(PathBuf::from)
+    }
+
+    /// Load the workspace named by `path()`, if any. A missing or malformed file is reported
+    /// with a warning and ignored, so startup falls back to the user config.
+    pub fn load() -> Option<Self> {
+        let path = Self::path()?;
+        let text = fs::read_to_string(&path)
+            .inspect_err(|e| warn!("Could not read workspace {}: {e}", path.display()))
+            .ok()?;
+        let mut workspace: Self = serde_json::from_str(&text)
+            .inspect_err(|e| warn!("Ignoring malformed workspace {}: {e}", path.display()))
+            .ok()?;
+        info!("Loaded workspace {}", path.display());
+        workspace.config.read_only = true;
+        Some(workspace)
+    }
+}
@@ -0,0 +1,52 @@
+//! Minimal dynamically loaded plugin for `PluginRegistry::load_from_path` tests.
+
+use lunaris_runtime::{
+    consts,
+    lunaris_api::{
+        plugin::{DynPlugin, PluginContext, PluginReport},
+        util::error::Result,
+    },
+    plugin::{PluginMetadata, RestartPolicy},
+    registry::PluginDeclaration,
+};
+
+struct TestPlugin;
+
+impl DynPlugin for TestPlugin {
+    fn name(&self) -> &'static str {
+        "test_plugin"
+    }
+    fn init(&self, _ctx: PluginContext<'_>) -> Result {
+        Ok(())
+    }
+    fn update_world(&mut self, _ctx: PluginContext<'_>) -> Result {
+        Ok(())
+    }
+    fn report(&self, _ctx: PluginContext<'_>) -> PluginReport {
+        unreachable!("not called by the registry tests")
+    }
+    fn shutdown(&mut self, _ctx: PluginContext<'_>) {}
+    fn reset(&mut self, _ctx: PluginContext<'_>) {}
+}
+
+#[unsafe(no_mangle)]
+#[allow(non_upper_case_globals)]
+pub static lunaris_plugin_abi_version: u32 = if cfg!(feature = "wrong-abi") {
+    consts::VERSION_MAJOR + 1
+} else {
+    consts::VERSION_MAJOR
+};
+
+#[cfg(not(feature = "missing-entry"))]
+#[unsafe(no_mangle)]
+pub fn lunaris_plugin_entry() -> PluginDeclaration {
+    let plugin: Box<dyn DynPlugin> = Box::new(TestPlugin);
+    PluginDeclaration {
+        plugin: Box::into_raw(plugin),
+        metadata: PluginMetadata {
+            version: Some("0.1.0"),
+            ..PluginMetadata::new("test_plugin")
+        },
+        restart_policy: RestartPolicy::Never,
+    }
+}
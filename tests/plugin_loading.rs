@@ -0,0 +1,54 @@
+//! Loads `tests/fixtures/test_plugin` through `PluginRegistry::load_from_path`. Needs the
+//! `fixture-plugins` feature: `cargo test --features fixture-plugins --test plugin_loading`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use lunaris_runtime::lunaris_api::util::error::LunarisError;
+use lunaris_runtime::registry::PluginRegistry;
+
+/// Build the fixture with `features` and copy the library out under a name of its own, since
+/// every variant is built to the same file.
+fn build_fixture(features: &str, name: &str) -> PathBuf {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let target = root.join("target").join("plugin-fixtures");
+    let status = Command::new(env!("CARGO"))
+        .arg("build")
+        .arg("--manifest-path")
+        .arg(root.join("tests/fixtures/test_plugin/Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target)
+        .args(["--features", features])
+        .status()
+        .expect("cargo should run");
+    assert!(status.success(), "building the fixture plugin failed");
+    let built = target
+        .join("debug")
+        .join(libloading::library_filename("lunaris_test_plugin"));
+    let copy = target.join(libloading::library_filename(name));
+    fs::copy(built, &copy).expect("fixture plugin should be built");
+    copy
+}
+
+#[test]
+fn test_load_from_path() {
+    let mut registry = PluginRegistry::default();
+    assert!(matches!(
+        registry.load_from_path(&build_fixture("missing-entry", "missing_entry")),
+        Err(LunarisError::PluginMissingSymbols { .. })
+    ));
+    assert!(matches!(
+        registry.load_from_path(&build_fixture("wrong-abi", "wrong_abi")),
+        Err(LunarisError::PluginIncompatible { .. })
+    ));
+    assert!(registry.is_empty());
+
+    let index = registry
+        .load_from_path(&build_fixture("", "working"))
+        .unwrap();
+    assert_eq!(index, 0);
+    let metadata: Vec<_> = registry.metadata().collect();
+    assert_eq!(metadata[0].name, "test_plugin");
+    assert_eq!(metadata[0].version, Some("0.1.0"));
+}